@@ -0,0 +1,87 @@
+use crate::options::RenderOptions;
+use crate::error::Error;
+
+use std::fs;
+use std::path::Path;
+
+/// カレントディレクトリから探す設定ファイルの名前
+pub const CONFIG_FILE: &str = "acscg.toml";
+
+/// メッセージの言語
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    Ja,
+    En,
+}
+
+impl Locale {
+    /// エラーメッセージをこの言語で得る
+    pub fn error_message(&self, err: &Error) -> String {
+        match self {
+            Locale::Ja => err.message_ja(),
+            Locale::En => err.message_en(),
+        }
+    }
+}
+
+/// 設定ファイル(acscg.toml)の内容
+///
+/// コマンドラインの引数で上書きされるデフォルト値として使う
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// htmlの出力先
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// 出力するカレンダー(入力ファイルのcalendarsのid). Noneならすべて
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub calendar: Option<String>,
+    /// メッセージの言語. Noneなら日本語と英語の両方
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<Locale>,
+    /// カレンダーの見た目に関する設定
+    #[serde(flatten)]
+    pub render: RenderOptions,
+}
+
+impl Config {
+    /// tomlの文字列からConfigを作る
+    pub fn from_toml(path: &str, toml: &str) -> Result<Config, Error> {
+        return toml::from_str(toml).map_err(|source| Error::Config { path: path.to_string(), source });
+    }
+
+    /// 設定ファイルを読み込む
+    pub fn from_file(path: &str) -> Result<Config, Error> {
+        let toml = fs::read_to_string(path).map_err(|source| Error::Io { path: path.to_string(), source })?;
+        return Config::from_toml(path, &toml);
+    }
+
+    /// 設定ファイルを探して読み込む
+    ///
+    /// pathが指定されていなければカレントディレクトリのacscg.tomlを読み、
+    /// それも無ければデフォルトの設定にする
+    pub fn load(path: Option<&str>) -> Result<Config, Error> {
+        if let Some(path) = path {
+            return Config::from_file(path);
+        }
+        if Path::new(CONFIG_FILE).is_file() {
+            return Config::from_file(CONFIG_FILE);
+        }
+
+        return Ok(Config::default());
+    }
+
+    /// tomlの文字列にする
+    pub fn to_toml(&self) -> Result<String, Error> {
+        return toml::to_string(self).map_err(|err| Error::Render(err.to_string()));
+    }
+
+    /// エラーメッセージを設定された言語で得る
+    pub fn error_message(&self, err: &Error) -> String {
+        match self.locale {
+            Some(locale) => locale.error_message(err),
+            None => err.to_string(),
+        }
+    }
+}
@@ -0,0 +1,670 @@
+use crate::structs::web::{ToHtml, element::Element, css::CSS, css::Media};
+use crate::structs::web::css::MakerCSSs;
+use crate::structs::input::Input;
+use crate::options::{RenderOptions, PrintOptions, Paper};
+use crate::error::Error;
+
+/// 今日の日付を強調し、過ぎた日を薄くするスクリプト
+/// bodyのdata-todayがあればその日付を今日として扱う
+/// 今日の月までスクロールし、凡例の次のイベントに印を付ける
+pub(crate) const TODAY_SCRIPT: &str = r#"document.addEventListener('DOMContentLoaded', function() {
+    var pad = function(n) { return (n < 10 ? '0' : '') + n; };
+    var now = new Date();
+    var today = document.body.getAttribute('data-today') || now.getFullYear() + '-' + pad(now.getMonth() + 1) + '-' + pad(now.getDate());
+    document.querySelectorAll('[data-date]').forEach(function(element) {
+        var date = element.getAttribute('data-date');
+        if (date === today) {
+            element.classList.add('today');
+            element.setAttribute('aria-current', 'date');
+        } else if (date < today) {
+            element.classList.add('past');
+        }
+    });
+    var month = document.querySelector('.calendar[data-month="' + today.slice(0, 7) + '"]');
+    if (month && month.previousElementSibling) {
+        month.scrollIntoView();
+    }
+    var next = null;
+    var nextDate = null;
+    document.querySelectorAll('[data-dates]').forEach(function(element) {
+        var date = element.getAttribute('data-dates').split(',').filter(function(date) { return date >= today; })[0];
+        if (date && (nextDate === null || date < nextDate)) {
+            next = element;
+            nextDate = date;
+        }
+    });
+    if (next) {
+        next.classList.add('next-event');
+    }
+});"#;
+
+/// 凡例のイベントをクリックして、そのイベントのマーカーを表示するか切り替えるスクリプト
+/// イベントはevent_index属性で区別し、表示するイベントをURLのhash("#events=0,2")に保存する
+/// カテゴリの見出しのチェックボックスはカテゴリのイベントをまとめて切り替える
+/// 複数のイベントが重なる日は、表示しているイベントのうち後に定義されたもので塗り直す
+pub(crate) const FILTER_SCRIPT: &str = r#"document.addEventListener('DOMContentLoaded', function() {
+    var items = document.querySelectorAll('.event-description .collection-item');
+    var toggles = document.querySelectorAll('.category-toggle');
+    var eventOf = function(element) {
+        return element.querySelector('[event_index]').getAttribute('event_index');
+    };
+    var allEvents = [];
+    items.forEach(function(item) {
+        allEvents.push(eventOf(item));
+    });
+    var parse = function() {
+        var match = location.hash.match(/^#events=([0-9,]*)$/);
+        return match ? match[1].split(',').filter(function(event) { return event !== ''; }) : null;
+    };
+    // 表示するイベント(nullなら全て)
+    var visible = parse();
+    var isVisible = function(event) {
+        return visible === null || visible.indexOf(event) >= 0;
+    };
+    var setVisible = function(event, shown) {
+        visible = (visible === null ? allEvents : visible).filter(function(other) { return other !== event; });
+        if (shown) {
+            visible.push(event);
+        }
+    };
+    var update = function() {
+        items.forEach(function(item) {
+            var shown = isVisible(eventOf(item));
+            item.classList.toggle('filtered-out', !shown);
+            item.setAttribute('aria-pressed', shown ? 'true' : 'false');
+        });
+        toggles.forEach(function(toggle) {
+            var events = [];
+            items.forEach(function(item) {
+                if (item.getAttribute('data-category') === toggle.getAttribute('data-category')) {
+                    events.push(eventOf(item));
+                }
+            });
+            var shown = events.filter(isVisible);
+            toggle.checked = shown.length > 0;
+            toggle.indeterminate = shown.length > 0 && shown.length < events.length;
+        });
+        document.querySelectorAll('[data-events]').forEach(function(marker) {
+            var events = marker.getAttribute('data-events').split(',').filter(isVisible);
+            if (events.length > 0) {
+                marker.setAttribute('event_index', events[events.length - 1]);
+            }
+            marker.classList.toggle('filtered', events.length === 0);
+        });
+        document.querySelectorAll('.agenda-body tr').forEach(function(row) {
+            row.classList.toggle('filtered-out', !isVisible(eventOf(row)));
+        });
+        document.querySelectorAll('.event-label').forEach(function(label) {
+            label.classList.toggle('filtered-out', !isVisible(label.getAttribute('data-event')));
+        });
+        document.querySelectorAll('.event-bar').forEach(function(bar) {
+            bar.classList.toggle('filtered', !isVisible(bar.getAttribute('event_index')));
+        });
+    };
+    var save = function() {
+        if (visible !== null && visible.length === allEvents.length) {
+            visible = null;
+        }
+        var hash = visible === null ? '' : '#events=' + visible.sort(function(a, b) { return a - b; }).join(',');
+        history.replaceState(null, '', location.pathname + location.search + hash);
+        update();
+    };
+    items.forEach(function(item) {
+        var toggle = function() {
+            var event = eventOf(item);
+            setVisible(event, !isVisible(event));
+            save();
+        };
+        item.addEventListener('click', toggle);
+        item.addEventListener('keydown', function(e) {
+            if (e.key === 'Enter' || e.key === ' ') {
+                e.preventDefault();
+                toggle();
+            }
+        });
+    });
+    toggles.forEach(function(toggle) {
+        toggle.addEventListener('change', function() {
+            items.forEach(function(item) {
+                if (item.getAttribute('data-category') === toggle.getAttribute('data-category')) {
+                    setVisible(eventOf(item), toggle.checked);
+                }
+            });
+            save();
+        });
+    });
+    // モーダルを閉じたときなどにhashが変わっても、絞り込みは保つ
+    window.addEventListener('hashchange', function() {
+        var hash = parse();
+        if (hash !== null || location.hash === '') {
+            visible = hash;
+            update();
+        } else if (visible !== null) {
+            save();
+        }
+    });
+    update();
+});"#;
+
+/// style領域(css)を追加
+pub(crate) fn create_style(input: &Input, options: &RenderOptions) -> Result<Element, Error> {
+    let mut styles = Element::create("div");
+    styles.add_class("styles");
+
+    let style_static = create_style_static();
+    styles.append(style_static);
+
+    let style_dynamic = create_style_dynamic(input, options)?;
+    styles.append(style_dynamic);
+
+    if let Some(print) = options.print {
+        let style_print = create_style_print(&print);
+        styles.append(style_print);
+    }
+
+    // 利用者のcssは最後に置いて、それまでのcssを上書きできるようにする
+    if let Some(style_user) = create_style_user(input, options)? {
+        styles.append(style_user);
+    }
+
+    return Ok(styles);
+}
+
+/// CSSのうち入力(input)によって変化しない部分を出力する
+fn create_style_static() -> Element {
+    let mut css_vec: Vec<CSS> = Vec::new();
+
+    let mut css = CSS::create("nav");
+    css.push_declaration("box-shadow", "none");
+    css_vec.push(css);
+
+    let mut css = CSS::create("header");
+    css.push_declaration("padding", "20px 0");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".event-description");
+    css.push_declaration("margin", "0 20px");
+    css.push_declaration("padding", "10px 0");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".event-description ul.collection, .event-description ul.collection li.collection-item");
+    css.push_declaration("border", "none");
+    css.push_declaration("background-color", "inherit");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".calendar-title");
+    css.push_declaration("width", "88vw");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".calendar-title i");
+    css.push_declaration("font-size", "80px");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".calendar-title .date");
+    css.push_declaration("padding", "10px 0");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".calendar-title .date .month");
+    css.push_declaration("font-size", "25px");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".circled");
+    css.push_declaration("padding", "10px");
+    css.push_declaration("border-radius", "5px");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".circled.digit");
+    css.push_declaration("padding-right", "14px");
+    css.push_declaration("padding-left", "14px");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".marker-letter");
+    css.push_declaration("font-size", "0.7em");
+    css.push_declaration("vertical-align", "super");
+    css.push_declaration("margin-left", "2px");
+    css_vec.push(css);
+
+    // 画面には表示せず、読み上げのみ行う
+    let mut css = CSS::create(".visually-hidden");
+    css.push_declaration("position", "absolute");
+    css.push_declaration("width", "1px");
+    css.push_declaration("height", "1px");
+    css.push_declaration("overflow", "hidden");
+    css.push_declaration("clip", "rect(0 0 0 0)");
+    css.push_declaration("white-space", "nowrap");
+    css_vec.push(css);
+
+    // 前後の月の日付
+    let mut css = CSS::create(".adjacent-day");
+    css.push_declaration("opacity", "0.4");
+    css_vec.push(css);
+
+    // カテゴリの見出しとイベントの絞り込み
+    let mut css = CSS::create(".event-description .collection-header");
+    css.push_declaration("border", "none");
+    css.push_declaration("background-color", "inherit");
+    css.push_declaration("font-weight", "bold");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".event-description .category-name");
+    css.push_declaration("color", "inherit");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".event-description .collection-item");
+    css.push_declaration("cursor", "pointer");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".event-description .filtered-out");
+    css.push_declaration("opacity", "0.4");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".agenda-body .filtered-out");
+    css.push_declaration("display", "none");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".circled.filtered");
+    css.push_declaration("background-color", "transparent !important");
+    css.push_declaration("color", "inherit !important");
+    css_vec.push(css);
+
+    // マス目をまたぐイベントの帯(マス目の左右の余白を打ち消してつなげる)
+    let mut css = CSS::create(".event-bar, .event-bar-space");
+    css.push_declaration("height", "1.5em");
+    css.push_declaration("margin", "2px -5px 0");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".event-bar");
+    css.push_declaration("line-height", "1.5em");
+    css.push_declaration("text-align", "left");
+    css.push_declaration("white-space", "nowrap");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".event-bar.bar-start");
+    css.push_declaration("margin-left", "0");
+    css.push_declaration("border-top-left-radius", "5px");
+    css.push_declaration("border-bottom-left-radius", "5px");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".event-bar.bar-end");
+    css.push_declaration("margin-right", "0");
+    css.push_declaration("border-top-right-radius", "5px");
+    css.push_declaration("border-bottom-right-radius", "5px");
+    css_vec.push(css);
+
+    // イベント名は隣のマス目の帯の上に重ねて表示する
+    let mut css = CSS::create(".event-bar-name");
+    css.push_declaration("position", "relative");
+    css.push_declaration("z-index", "1");
+    css.push_declaration("padding-left", "5px");
+    css.push_declaration("font-size", "0.8em");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".event-bar.filtered");
+    css.push_declaration("visibility", "hidden");
+    css_vec.push(css);
+
+    // マス目のイベント名(長いときは省略する)
+    let mut css = CSS::create("table.calendar-body.detailed");
+    css.push_declaration("table-layout", "fixed");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".event-label");
+    css.push_declaration("overflow", "hidden");
+    css.push_declaration("text-overflow", "ellipsis");
+    css.push_declaration("white-space", "nowrap");
+    css.push_declaration("text-align", "left");
+    css.push_declaration("font-size", "0.75em");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".event-label-time");
+    css.push_declaration("margin-right", "4px");
+    css.push_declaration("opacity", "0.7");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".event-label.filtered-out");
+    css.push_declaration("display", "none");
+    css_vec.push(css);
+
+    // 未定・中止の開催日
+    let mut css = CSS::create(".cancelled");
+    css.push_declaration("text-decoration", "line-through");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".circled.cancelled");
+    css.push_declaration("opacity", "0.6");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".tentative");
+    css.push_declaration("outline", "1px dashed currentColor");
+    css.push_declaration("outline-offset", "2px");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".event-status, .day-detail-status");
+    css.push_declaration("margin-left", "10px");
+    css.push_declaration("font-size", "0.8em");
+    css_vec.push(css);
+
+    // 週番号
+    let mut css = CSS::create(".week-number");
+    css.push_declaration("font-size", "0.8em");
+    css.push_declaration("font-weight", "normal");
+    css.push_declaration("white-space", "nowrap");
+    css.push_declaration("opacity", "0.6");
+    css_vec.push(css);
+
+    // 過ぎた日
+    let mut css = CSS::create(".past");
+    css.push_declaration("opacity", "0.5");
+    css_vec.push(css);
+
+    // 凡例の次のイベント
+    let mut css = CSS::create(".next-event .description");
+    css.push_declaration("font-weight", "bold");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".next-event .description::after");
+    css.push_declaration("content", "\"(次のイベント)\"");
+    css.push_declaration("margin-left", "10px");
+    css.push_declaration("font-size", "0.8em");
+    css.push_declaration("font-weight", "normal");
+    css_vec.push(css);
+
+    // イベントのある日の詳細
+    let mut css = CSS::create(".calendar-body a.modal-trigger");
+    css.push_declaration("color", "inherit");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".day-detail-name, .day-detail-time, .day-detail-location");
+    css.push_declaration("margin-left", "10px");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".day-detail");
+    css.push_declaration("position", "relative");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".day-detail summary");
+    css.push_declaration("cursor", "pointer");
+    css.push_declaration("list-style", "none");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".day-detail-body");
+    css.push_declaration("position", "absolute");
+    css.push_declaration("z-index", "10");
+    css.push_declaration("left", "50%");
+    css.push_declaration("transform", "translateX(-50%)");
+    css.push_declaration("min-width", "240px");
+    css.push_declaration("text-align", "left");
+    css.push_declaration("box-shadow", "0 2px 8px rgba(0,0,0,0.3)");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".agenda");
+    css.push_declaration("margin", "0 20px");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".agenda-title");
+    css.push_declaration("font-size", "25px");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".agenda-body .agenda-date");
+    css.push_declaration("width", "80px");
+    css.push_declaration("text-align", "center");
+    css_vec.push(css);
+
+    let mut css = CSS::create(".agenda-body .agenda-weekday");
+    css.push_declaration("width", "60px");
+    css_vec.push(css);
+
+    // todo remove non-static
+    let mut css = CSS::create(".circled.red, .circled.blue");
+    css.push_declaration("color", "white");
+    css_vec.push(css);
+
+    // 印刷時のcss
+    let mut media_print = Media::create("print");
+
+    // navは色を落として印刷する
+    let mut css = CSS::create("nav");
+    css.push_declaration("background-color", "white !important");
+    media_print.push_css(css);
+
+    let mut css = CSS::create("nav .brand-logo");
+    css.push_declaration("color", "black");
+    media_print.push_css(css);
+
+    // 印刷ではマウスを乗せて全文を見られないので、イベント名を折り返して表示する
+    let mut css = CSS::create(".event-label");
+    css.push_declaration("white-space", "normal");
+    css.push_declaration("overflow", "visible");
+    media_print.push_css(css);
+
+    // 印刷物は日付が変わっても使うので、今日の強調は印刷しない
+    let mut css = CSS::create(".past");
+    css.push_declaration("opacity", "1");
+    media_print.push_css(css);
+
+    let mut css = CSS::create("td.today");
+    css.push_declaration("box-shadow", "none");
+    media_print.push_css(css);
+
+    let mut css = CSS::create(".next-event .description::after");
+    css.push_declaration("content", "none");
+    media_print.push_css(css);
+
+    // 画面幅に依存しないようにする
+    let mut css = CSS::create(".calendar-title");
+    css.push_declaration("width", "100%");
+    media_print.push_css(css);
+
+    // 日付ごとの詳細は印刷しない
+    let mut css = CSS::create(".day-details, .day-detail-body");
+    css.push_declaration("display", "none");
+    media_print.push_css(css);
+
+    // ナビゲーション用のアイコンは印刷しない
+    let mut css = CSS::create(".calendar-title i");
+    css.push_declaration("display", "none");
+    media_print.push_css(css);
+
+    // マーカーの背景色を印刷させる
+    let mut css = CSS::create(".circled");
+    css.push_declaration("-webkit-print-color-adjust", "exact");
+    css.push_declaration("print-color-adjust", "exact");
+    media_print.push_css(css);
+
+    // 月の途中で改ページしない
+    let mut css = CSS::create(".calendar, .agenda-month");
+    css.push_declaration("page-break-inside", "avoid");
+    css.push_declaration("break-inside", "avoid");
+    media_print.push_css(css);
+
+    let mut style = String::new();
+    for static_css in css_vec {
+        style = format!("{}{}", style, static_css.to_html());
+    }
+    style = format!("{}{}", style, media_print.to_html());
+
+    let mut css = Element::create("style");
+    css.set_html(&style);
+
+    return css;
+}
+
+/// CSSのうち入力(input)や配色によって変化する部分を出力する
+fn create_style_dynamic(input: &Input, options: &RenderOptions) -> Result<Element, Error> {
+    let mut css = Element::create("style");
+
+    let css_vec = create_theme_csss(input, options)?;
+
+    // 端末がダークモードのときの配色
+    // 印刷には適用しない
+    let mut media_dark = Media::create("screen and (prefers-color-scheme: dark)");
+    if let Some(dark) = options.dark_variant()? {
+        for css in create_theme_csss(input, &dark)? {
+            media_dark.push_css(css);
+        }
+    }
+
+    // 印刷時のマーカー
+    let mut media_print = Media::create("print");
+
+    for i in 0..input.events.len() {
+        let pattern = options.event_pattern(input, i as i32)?;
+        for css in pattern.to_print_csss_for(i as u32) {
+            media_print.push_css(css);
+        }
+    }
+
+    let mut style = String::new();
+    for css in css_vec {
+        style = format!("{}{}", style, css.to_html());
+    }
+    if options.dark_variant()?.is_some() {
+        style = format!("{}{}", style, media_dark.to_html());
+    }
+    style = format!("{}{}", style, media_print.to_html());
+
+    css.set_html(&style);
+
+    return Ok(css);
+}
+
+/// 配色(テーマとマーカーの色)のcss
+fn create_theme_csss(input: &Input, options: &RenderOptions) -> Result<Vec<CSS>, Error> {
+    let theme = options.theme()?;
+    let mut css_vec: Vec<CSS> = Vec::new();
+
+    let mut css = CSS::create("body");
+    css.push_declaration("background-color", theme.background);
+    css.push_declaration("color", theme.text);
+    css_vec.push(css);
+
+    let mut css = CSS::create("nav");
+    css.push_declaration("background-color", options.nav_background()?);
+    css.push_declaration("font-family", options.nav_font()?);
+    css_vec.push(css);
+
+    let mut css = CSS::create("nav .brand-logo");
+    css.push_declaration("color", theme.nav_text);
+    css_vec.push(css);
+
+    let mut css = CSS::create(".event-description");
+    css.push_declaration("background-color", options.legend_background()?);
+    css.push_declaration("border-left", &format!("{} solid 3px", options.accent_color()?));
+    css_vec.push(css);
+
+    let mut css = CSS::create(".calendar-title i");
+    css.push_declaration("color", theme.icon);
+    css_vec.push(css);
+
+    let mut css = CSS::create(".calendar-title .date, .agenda-title");
+    css.push_declaration("color", theme.title);
+    css_vec.push(css);
+
+    let mut css = CSS::create("tr");
+    css.push_declaration("border-bottom-color", theme.border);
+    css_vec.push(css);
+
+    // 未定の日のマーカー(マーカーの文字は白なので、枠線は本文の色にする)
+    let mut css = CSS::create(".circled.tentative");
+    css.push_declaration("outline", &format!("2px dashed {}", theme.text));
+    css_vec.push(css);
+
+    // 今日の日付
+    let mut css = CSS::create("td.today");
+    css.push_declaration("box-shadow", &format!("inset 0 0 0 2px {}", options.accent_color()?));
+    css_vec.push(css);
+
+    let mut css = CSS::create(".modal, .modal .modal-footer, .day-detail-body, .day-detail-body .collection-item");
+    css.push_declaration("background-color", theme.background);
+    css.push_declaration("color", theme.text);
+    css_vec.push(css);
+
+    let mut css = CSS::create(".red-text");
+    css.push_declaration("color", &format!("{} !important", theme.sunday));
+    css_vec.push(css);
+
+    let mut css = CSS::create(".blue-text");
+    css.push_declaration("color", &format!("{} !important", theme.saturday));
+    css_vec.push(css);
+
+    for i in 0..input.events.len() {
+        let color = options.event_color(input, i as i32)?;
+        css_vec.append(&mut MakerCSSs::csss_from_colorcode(i as u32, &color, true));
+    }
+
+    return Ok(css_vec);
+}
+
+/// 利用者が指定したcss(設定のcss_file, css, 入力ファイルのcss)を出力する
+fn create_style_user(input: &Input, options: &RenderOptions) -> Result<Option<Element>, Error> {
+    let mut style = String::new();
+
+    if let Some(ref path) = options.css_file {
+        let css = std::fs::read_to_string(path).map_err(|source| Error::Io { path: path.clone(), source })?;
+        style.push_str(&css);
+    }
+    for css in options.css.iter().chain(input.css.iter()) {
+        style.push_str(css);
+    }
+
+    if style.len() == 0 {
+        return Ok(None);
+    }
+
+    let mut css = Element::create("style");
+    css.set_html(&style);
+
+    return Ok(Some(css));
+}
+
+/// 印刷モードのcss(用紙サイズと改ページ)を出力する
+fn create_style_print(print: &PrintOptions) -> Element {
+    let mut css_vec: Vec<CSS> = Vec::new();
+
+    // 用紙サイズ
+    let mut css = CSS::create("@page");
+    css.push_declaration("size", match print.paper {
+        Paper::A4 => "A4 portrait",
+        Paper::A3 => "A3 portrait",
+    });
+    css.push_declaration("margin", "10mm");
+    css_vec.push(css);
+
+    let mut media_print = Media::create("print");
+
+    // 1ページに2ヶ月より多く並べるときは2列にする
+    let columns = if print.months_per_page > 2 { 2 } else { 1 };
+    let rows = print.months_per_page.div_ceil(columns);
+
+    let mut css = CSS::create(".calendars");
+    css.push_declaration("display", "flex");
+    css.push_declaration("flex-wrap", "wrap");
+    media_print.push_css(css);
+
+    let mut css = CSS::create(".calendar");
+    css.push_declaration("width", &format!("{}%", 100 / columns));
+    css.push_declaration("padding", "0 5mm");
+    media_print.push_css(css);
+
+    // 行数が増えたらマス目の余白を詰めて1ページに収める
+    let mut css = CSS::create(".calendar-body td, .calendar-body th");
+    css.push_declaration("padding", &format!("{}px 0", 12 / rows.max(1)));
+    media_print.push_css(css);
+
+    // months_per_page個ごとに改ページする
+    let mut css = CSS::create(&format!(".calendars .calendar:nth-child({}n)", print.months_per_page.max(1)));
+    css.push_declaration("page-break-after", "always");
+    css.push_declaration("break-after", "page");
+    media_print.push_css(css);
+
+    let mut style = String::new();
+    for css in css_vec {
+        style = format!("{}{}", style, css.to_html());
+    }
+    style = format!("{}{}", style, media_print.to_html());
+
+    let mut css = Element::create("style");
+    css.set_html(&style);
+
+    return css;
+}
@@ -0,0 +1,54 @@
+use crate::error::Error;
+
+use resvg::{tiny_skia, usvg};
+use resvg::usvg::fontdb;
+
+/// create_svgで作成したsvgをpngに変換する
+/// fontが指定されればそのフォントで、されなければシステムのフォントで文字を描く
+pub fn create(svg: &str, font: Option<&str>) -> Result<Vec<u8>, Error> {
+    let mut options = usvg::Options::default();
+    load_fonts(options.fontdb_mut(), font)?;
+
+    let tree = usvg::Tree::from_str(svg, &options).map_err(|err| Error::Render(format!("svg: {}", err)))?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or(Error::Render(format!("{}x{}", size.width(), size.height())))?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    return pixmap.encode_png().map_err(|err| Error::Render(format!("png: {}", err)));
+}
+
+/// 文字の描画に用いるフォントを読み込む
+fn load_fonts(fontdb: &mut fontdb::Database, font: Option<&str>) -> Result<(), Error> {
+    fontdb.load_system_fonts();
+
+    // 指定されたフォントをsans-serifとして使う(日本語のフォントを想定)
+    if let Some(path) = font {
+        let data = std::fs::read(path).map_err(|source| Error::Io { path: path.to_string(), source })?;
+        let before = fontdb.len();
+        fontdb.load_font_data(data);
+        let family = fontdb.faces().nth(before).map(|face| face.families[0].0.clone());
+        match family {
+            Some(family) => fontdb.set_sans_serif_family(family),
+            None => return Err(Error::Render(format!("font: {}", path))),
+        }
+        return Ok(());
+    }
+
+    // sans-serifに対応するフォントがなければ、読み込めたフォントのうち名前に"Sans"を含むもの
+    // (なければ最初のもの)を使う
+    // 日本語などそのフォントにない文字は、その文字を持つ別のフォントで描かれる
+    let query = fontdb::Query { families: &[fontdb::Family::SansSerif], ..fontdb::Query::default() };
+    if fontdb.query(&query).is_none() {
+        let family = fontdb.faces()
+            .find(|face| face.families[0].0.contains("Sans"))
+            .or_else(|| fontdb.faces().next())
+            .map(|face| face.families[0].0.clone());
+        if let Some(family) = family {
+            fontdb.set_sans_serif_family(family.clone());
+            fontdb.set_serif_family(family);
+        }
+    }
+
+    return Ok(());
+}
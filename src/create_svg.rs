@@ -0,0 +1,213 @@
+use crate::structs::web::{ToHtml, element::Element};
+use crate::structs::input::Input;
+use crate::schedule::{calc_calendar, split_monthly, month_name};
+use crate::options::RenderOptions;
+use crate::theme::Theme;
+use crate::error::Error;
+
+use chrono::{NaiveDate, Weekday, Datelike};
+
+/// 出力するsvgの大きさ(px)
+#[derive(Debug, Clone, Copy)]
+pub struct Size {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Size {
+    /// "1080x1350"のような文字列からSizeを得る
+    pub fn from_name(name: &str) -> Option<Size> {
+        let mut split = name.split('x');
+        let width = split.next()?.parse::<u32>().ok()?;
+        let height = split.next()?.parse::<u32>().ok()?;
+        if split.next().is_some() || width == 0 || height == 0 {
+            return None;
+        }
+        return Some(Size { width, height });
+    }
+}
+
+impl Default for Size {
+    fn default() -> Self {
+        return Size { width: 1080, height: 1350 };
+    }
+}
+
+/// すべての月を縦に並べた1枚のsvgを出力する
+pub fn create(input: &Input, size: Size, options: &RenderOptions) -> Result<String, Error> {
+    let schedules_monthly = split_monthly(calc_calendar(input)?);
+    let svg = create_svg(input, &schedules_monthly, size, options)?;
+
+    return Ok(svg.to_html_index_noted(0));
+}
+
+/// 月ごとにsvgを出力する
+/// (月の初日, svg)の列を返す
+pub fn create_monthly(input: &Input, size: Size, options: &RenderOptions) -> Result<Vec<(NaiveDate, String)>, Error> {
+    let mut svgs = Vec::new();
+
+    for schedule_monthly in split_monthly(calc_calendar(input)?) {
+        let (first_day, _) = schedule_monthly[0];
+        let svg = create_svg(input, &[schedule_monthly], size, options)?;
+        svgs.push((first_day, svg.to_html_index_noted(0)));
+    }
+
+    return Ok(svgs);
+}
+
+/// タイトル、月ごとのカレンダー、凡例を縦に並べたsvg要素を作成する
+fn create_svg(input: &Input, schedules_monthly: &[Vec<(NaiveDate, Vec<i32>)>], size: Size, options: &RenderOptions) -> Result<Element, Error> {
+    let width = size.width as f64;
+    let height = size.height as f64;
+
+    let mut svg = Element::create("svg");
+    svg.set_attribute("xmlns", "http://www.w3.org/2000/svg");
+    svg.set_attribute("width", &format!("{}", size.width));
+    svg.set_attribute("height", &format!("{}", size.height));
+    svg.set_attribute("viewBox", &format!("0 0 {} {}", size.width, size.height));
+    svg.set_attribute("font-family", "sans-serif");
+
+    // 背景
+    let theme = options.theme()?;
+    svg.append(create_rect(0.0, 0.0, width, height, theme.background, 0.0));
+
+    // タイトル
+    let title_height = height * 0.08;
+    svg.append(create_rect(0.0, 0.0, width, title_height, options.nav_background()?, 0.0));
+    svg.append(create_text(width / 2.0, title_height * 0.65, title_height * 0.45, theme.nav_text, &input.title));
+
+    // 凡例
+    let line_height = (height * 0.04).min(40.0);
+    let legend_height = line_height * (input.shown_events().len() as f64 + 1.0);
+    let legend = create_legend(input, height - legend_height, width, line_height, options)?;
+
+    // 月ごとのカレンダー
+    let months_top = title_height;
+    let month_height = (height - title_height - legend_height) / schedules_monthly.len().max(1) as f64;
+    for (i, schedule_monthly) in schedules_monthly.iter().enumerate() {
+        let month = create_month(input, schedule_monthly, months_top + month_height * i as f64, width, month_height, options)?;
+        svg.append(month);
+    }
+
+    svg.append(legend);
+
+    return Ok(svg);
+}
+
+/// 1ヶ月分のカレンダーを作成する
+fn create_month(input: &Input, schedule_monthly: &[(NaiveDate, Vec<i32>)], top: f64, width: f64, height: f64, options: &RenderOptions) -> Result<Element, Error> {
+    let theme = options.theme()?;
+    let mut g = Element::create("g");
+    g.add_class("calendar");
+
+    let margin = width * 0.04;
+    let cell_width = (width - margin * 2.0) / 7.0;
+
+    // 月の名前
+    let (ref first_day, _) = schedule_monthly[0];
+    let title_height = height * 0.16;
+    let month_name = format!("{:?} {}", month_name(first_day)?, first_day.year());
+    g.append(create_text(width / 2.0, top + title_height * 0.7, title_height * 0.45, theme.title, &month_name));
+
+    // 曜日
+    let header_height = height * 0.1;
+    for (i, weekday) in options.week_start.weekdays().iter().enumerate() {
+        let weekday = *weekday;
+        let x = margin + cell_width * (i as f64 + 0.5);
+        let y = top + title_height + header_height * 0.7;
+        g.append(create_text(x, y, header_height * 0.45, weekday_color(theme, weekday), &format!("{:?}.", weekday)));
+    }
+
+    // 日付
+    let offset = options.week_start.offset(first_day.weekday()) as usize;
+    let weeks = (offset + schedule_monthly.len()).div_ceil(7);
+    let body_top = top + title_height + header_height;
+    let cell_height = (height - title_height - header_height) / weeks as f64;
+    let font_size = (cell_height * 0.4).min(cell_width * 0.4);
+
+    for (index, (day, events)) in schedule_monthly.iter().enumerate() {
+        let column = (offset + index) % 7;
+        let row = (offset + index) / 7;
+        let x = margin + cell_width * column as f64;
+        let y = body_top + cell_height * row as f64;
+
+        // イベントがある日はマーカーを描く
+        // 複数のイベントが重なる日は後に定義されたイベントで塗る
+        let color = match events.last() {
+            Some(event_index) => {
+                let colorcode = options.event_color(input, *event_index)?;
+                let padding = cell_width.min(cell_height) * 0.12;
+                g.append(create_rect(x + padding, y + padding, cell_width - padding * 2.0, cell_height - padding * 2.0, &colorcode, padding));
+                "white"
+            }
+            None => weekday_color(theme, day.weekday()),
+        };
+
+        g.append(create_text(x + cell_width / 2.0, y + cell_height / 2.0 + font_size * 0.35, font_size, color, &format!("{}", day.day())));
+    }
+
+    return Ok(g);
+}
+
+/// 凡例を作成する
+fn create_legend(input: &Input, top: f64, width: f64, line_height: f64, options: &RenderOptions) -> Result<Element, Error> {
+    let theme = options.theme()?;
+    let mut g = Element::create("g");
+    g.add_class("event-description");
+
+    let margin = width * 0.04;
+    let shown = input.shown_events();
+    g.append(create_rect(margin, top, width - margin * 2.0, line_height * (shown.len() as f64 + 0.5), options.legend_background()?, 0.0));
+    g.append(create_rect(margin, top, 3.0, line_height * (shown.len() as f64 + 0.5), options.accent_color()?, 0.0));
+
+    for (row, i) in shown.into_iter().enumerate() {
+        let event = &input.events[i];
+        let y = top + line_height * (row as f64 + 0.25);
+        let colorcode = options.event_color(input, i as i32)?;
+        let marker = line_height * 0.6;
+        g.append(create_rect(margin * 2.0, y + line_height * 0.2, marker, marker, &colorcode, marker * 0.2));
+
+        let mut text = create_text(margin * 2.0 + marker * 1.5, y + line_height * 0.7, line_height * 0.5, theme.title, &event.name);
+        text.set_attribute("text-anchor", "start");
+        g.append(text);
+    }
+
+    return Ok(g);
+}
+
+/// 曜日に応じた文字色(日曜は赤、土曜は青)
+fn weekday_color(theme: &Theme, weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Sun => theme.sunday,
+        Weekday::Sat => theme.saturday,
+        _ => theme.title,
+    }
+}
+
+/// rect要素を作成する
+fn create_rect(x: f64, y: f64, width: f64, height: f64, fill: &str, radius: f64) -> Element {
+    let mut rect = Element::create("rect");
+    rect.set_attribute("x", &format!("{:.1}", x));
+    rect.set_attribute("y", &format!("{:.1}", y));
+    rect.set_attribute("width", &format!("{:.1}", width));
+    rect.set_attribute("height", &format!("{:.1}", height));
+    if radius > 0.0 {
+        rect.set_attribute("rx", &format!("{:.1}", radius));
+    }
+    rect.set_attribute("fill", fill);
+
+    return rect;
+}
+
+/// 中央揃えのtext要素を作成する
+fn create_text(x: f64, y: f64, font_size: f64, fill: &str, text: &str) -> Element {
+    let mut elem = Element::create("text");
+    elem.set_attribute("x", &format!("{:.1}", x));
+    elem.set_attribute("y", &format!("{:.1}", y));
+    elem.set_attribute("font-size", &format!("{:.1}", font_size));
+    elem.set_attribute("fill", fill);
+    elem.set_attribute("text-anchor", "middle");
+    elem.set_text(&text.to_string());
+
+    return elem;
+}
@@ -0,0 +1,369 @@
+use crate::structs::web::ToHtml;
+use crate::structs::web::element::escape;
+use crate::structs::input::Input;
+use crate::structs::input::event::Status;
+use crate::schedule::{calc_calendar, split_monthly, split_weekly, month_name, week_label, week_bars, marker_events, event_label_title, BarPiece, event_letter, event_dates, event_status, event_overall_status, event_status_notes, day_title, day_label, events_on};
+use crate::options::{RenderOptions, CellMode};
+use crate::create_html;
+use crate::error::Error;
+
+use chrono::{NaiveDate, Weekday, Datelike, Duration};
+use minijinja::{Environment, AutoEscape, Value, context};
+
+/// 組み込みのテンプレート(Materializeのレイアウト)
+/// --templateの指定がなければこのテンプレートで出力する
+pub const DEFAULT_TEMPLATE: &str = include_str!("../templates/calendar.html.j2");
+
+/// テンプレートに渡すイベント
+#[derive(Debug, Serialize)]
+struct EventContext<'a> {
+    /// イベントのindex(マーカーのevent_index)
+    index: usize,
+    /// イベントを示すアルファベット(A, B, ...)
+    letter: String,
+    name: &'a str,
+    time: Option<&'a str>,
+    location: Option<&'a str>,
+    url: Option<&'a str>,
+    /// マーカーの色
+    color: String,
+    /// "2019-04-08"のような開催日の一覧(日付順). 中止の日は含めない
+    dates: Vec<String>,
+    category: Option<&'a str>,
+    /// すべての開催日に共通する状態(開催日によって違えば確定)
+    status: StatusContext,
+    /// 未定・中止の開催日の説明
+    status_notes: Vec<StatusNoteContext>,
+    /// 凡例に表示するか(--calendarで選んだカレンダーに載っていなければfalse)
+    shown: bool,
+}
+
+/// テンプレートに渡す開催日の状態
+#[derive(Debug, Serialize)]
+struct StatusContext {
+    /// "confirmed"・"tentative"・"cancelled"
+    name: &'static str,
+    /// "未定"・"中止"(確定ならnone)
+    label: Option<&'static str>,
+}
+
+/// テンプレートに渡す未定・中止の開催日の説明
+#[derive(Debug, Serialize)]
+struct StatusNoteContext {
+    status: StatusContext,
+    /// "4/24 中止"のような説明
+    text: String,
+}
+
+/// テンプレートに渡す凡例のカテゴリ
+#[derive(Debug, Serialize)]
+struct CategoryContext<'a> {
+    /// 見出しに表示する名前(カテゴリのないイベントは"その他")
+    name: &'a str,
+    /// 絞り込みに使う値(カテゴリのないイベントは空)
+    key: &'a str,
+    /// カテゴリのイベントのindex一覧
+    events: Vec<usize>,
+}
+
+/// テンプレートに渡す曜日
+#[derive(Debug, Serialize)]
+struct WeekdayContext {
+    /// "Sun."のような曜日の名前
+    name: String,
+    /// 日曜は"red-text"、土曜は"blue-text"、それ以外は空
+    class: &'static str,
+}
+
+/// テンプレートに渡す日付
+#[derive(Debug, Serialize)]
+struct DayContext {
+    day: u32,
+    /// "2019-04-08"のような日付
+    date: String,
+    /// 詳細(モーダル)のid
+    id: String,
+    /// "4月10日 水曜日"のような見出し
+    title: String,
+    weekday: WeekdayContext,
+    /// 読み上げ用の説明("4月10日 水曜日: ミーティング")
+    label: String,
+    /// その日に開催されるイベントのアルファベット
+    letters: String,
+    /// その日に開催されるイベントのindex一覧
+    events: Vec<i32>,
+    /// マーカーで示すイベントのindex一覧(帯で表示するイベントを除く)
+    markers: Vec<i32>,
+    /// マーカーの色に使うイベントのindex(後に定義されたイベント)
+    event: Option<i32>,
+    /// マーカーの色に使うイベントの状態
+    status: StatusContext,
+    /// その日に開催されるイベントそれぞれの状態(eventsと同じ順)
+    statuses: Vec<StatusContext>,
+    /// 前後の月の日付か
+    adjacent: bool,
+    /// マス目をまたぐイベントの帯(段ごと、帯のない段はnone)
+    bars: Vec<Option<BarContext>>,
+    /// 日付の下に表示するイベント名(cellsがdetailedでなければ空)
+    labels: Vec<LabelContext>,
+}
+
+/// テンプレートに渡すマス目のイベント名
+#[derive(Debug, Serialize)]
+struct LabelContext {
+    /// イベントのindex
+    event: i32,
+    name: String,
+    time: Option<String>,
+    /// マウスを乗せたときに表示する全文("18:30-20:00 ミーティング(中止)")
+    title: String,
+    status: StatusContext,
+}
+
+/// テンプレートに渡すイベントの帯の1日分
+#[derive(Debug, Serialize)]
+struct BarContext {
+    /// イベントのindex
+    event: i32,
+    /// 週の中で帯が始まる日か(イベント名を表示する)
+    start: bool,
+    /// 週の中で帯が終わる日か
+    end: bool,
+    status: StatusContext,
+}
+
+/// テンプレートに渡す一覧表示の1行
+#[derive(Debug, Serialize)]
+struct AgendaContext<'a> {
+    /// "4/8"のような日付
+    date: String,
+    /// "2019-04-08"のような日付
+    iso_date: String,
+    weekday: WeekdayContext,
+    event: EventContext<'a>,
+    status: StatusContext,
+}
+
+/// テンプレートに渡す1ヶ月分のカレンダー
+#[derive(Debug, Serialize)]
+struct MonthContext<'a> {
+    /// "April"のような月の名前
+    name: String,
+    year: i32,
+    month: u32,
+    /// "2019-04"のような年月
+    date: String,
+    /// 週ごとの日付(adjacent_daysがfalseなら月の前後の空白はnone)
+    weeks: Vec<Vec<Option<DayContext>>>,
+    /// 週ごとの週番号の表示(weeksと同じ順、表示しない週はnone)
+    week_labels: Vec<Option<String>>,
+    /// その月に開催されるイベントの一覧
+    agenda: Vec<AgendaContext<'a>>,
+}
+
+/// テンプレートファイルを読み込んでカレンダーのhtmlを出力する
+pub fn create_from_file(input: &Input, path: &str, options: &RenderOptions) -> Result<String, Error> {
+    let template = std::fs::read_to_string(path).map_err(|source| Error::Io { path: path.to_string(), source })?;
+    return create(input, path, &template, options);
+}
+
+/// テンプレート(minijinja)に従ってカレンダーのhtmlを出力する
+/// nameはエラーメッセージに表示するテンプレートの名前
+pub fn create(input: &Input, name: &str, template: &str, options: &RenderOptions) -> Result<String, Error> {
+    let mut env = Environment::new();
+    // 拡張子によらず、値はhtmlとしてエスケープする
+    env.set_auto_escape_callback(|_| AutoEscape::Html);
+    // 文字列はElement(svg・css)と同じ文字参照でエスケープする
+    env.set_formatter(|out, state, value| {
+        if state.auto_escape() == AutoEscape::Html && !value.is_safe() {
+            if let Some(text) = value.as_str() {
+                return out.write_str(&escape(text)).map_err(minijinja::Error::from);
+            }
+        }
+        return minijinja::escape_formatter(out, state, value);
+    });
+    env.add_template(name, template).map_err(Error::Template)?;
+
+    let mut events = Vec::new();
+    for i in 0..input.events.len() {
+        events.push(event_context(input, i, options)?);
+    }
+
+    // カテゴリのない入力では空
+    let mut categories = Vec::new();
+    if input.categories().len() > 0 {
+        for (category, events) in input.legend_groups() {
+            categories.push(CategoryContext { name: category.unwrap_or("その他"), key: category.unwrap_or(""), events });
+        }
+    }
+
+    let weekdays: Vec<WeekdayContext> = options.week_start.weekdays().iter().map(|weekday| weekday_context(*weekday)).collect();
+
+    let schedule = calc_calendar(input)?;
+    let mut months = Vec::new();
+    for schedule_monthly in split_monthly(schedule.clone()) {
+        months.push(month_context(input, &schedule, &schedule_monthly, options)?);
+    }
+
+    // 入力と設定に応じたcss(テンプレートによらず共通)
+    let style = create_html::create_style(input, options)?.to_html();
+
+    let context = context! {
+        title => &input.title,
+        lang => &options.lang,
+        year => input.year,
+        materialize_css => &options.materialize_css,
+        materialize_js => &options.materialize_js,
+        material_icons => &options.material_icons,
+        grid => options.layout.has_grid(),
+        agenda => options.layout.has_agenda(),
+        popover => options.popover,
+        cells => options.cells,
+        week_numbers => options.week_numbers,
+        events => events,
+        categories => categories,
+        weekdays => weekdays,
+        months => months,
+        style => Value::from_safe_string(style),
+        today => options.today()?.map(|today| today.format("%Y-%m-%d").to_string()),
+        today_script => Value::from_safe_string(create_html::TODAY_SCRIPT.to_string()),
+        filter_script => Value::from_safe_string(create_html::FILTER_SCRIPT.to_string()),
+    };
+
+    let template = env.get_template(name).map_err(Error::Template)?;
+    return template.render(context).map_err(Error::Template);
+}
+
+/// i番目のイベント
+fn event_context<'a>(input: &'a Input, i: usize, options: &RenderOptions) -> Result<EventContext<'a>, Error> {
+    let event = &input.events[i];
+    return Ok(EventContext {
+        index: i,
+        letter: event_letter(i as i32),
+        name: &event.name,
+        time: event.time.as_deref(),
+        location: event.location.as_deref(),
+        url: event.url.as_deref(),
+        color: options.event_color(input, i as i32)?,
+        dates: event_dates(input, i)?.iter()
+            .filter(|date| event_status(input, i as i32, date) != Status::Cancelled)
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .collect(),
+        category: event.category.as_deref(),
+        status: status_context(event_overall_status(input, i)),
+        status_notes: event_status_notes(input, i).into_iter().map(|(status, text)| StatusNoteContext { status: status_context(status), text }).collect(),
+        shown: input.is_shown(i),
+    });
+}
+
+/// 曜日の名前とclass
+fn weekday_context(weekday: Weekday) -> WeekdayContext {
+    let class = match weekday {
+        Weekday::Sun => "red-text",
+        Weekday::Sat => "blue-text",
+        _ => "",
+    };
+    return WeekdayContext { name: format!("{:?}.", weekday), class };
+}
+
+/// 開催日の状態
+fn status_context(status: Status) -> StatusContext {
+    return StatusContext { name: status.name(), label: status.label() };
+}
+
+/// 日付
+fn day_context(input: &Input, day: &NaiveDate, events: &[i32], adjacent: bool, bars: &[Option<BarPiece>], options: &RenderOptions) -> DayContext {
+    let markers = marker_events(events, bars);
+    let mut labels = Vec::new();
+    if options.cells == CellMode::Detailed {
+        for event_index in &markers {
+            let event = &input.events[*event_index as usize];
+            labels.push(LabelContext {
+                event: *event_index,
+                name: event.name.clone(),
+                time: event.time.clone(),
+                title: event_label_title(input, *event_index, day),
+                status: status_context(event_status(input, *event_index, day)),
+            });
+        }
+    }
+    return DayContext {
+        day: day.day(),
+        date: day.format("%Y-%m-%d").to_string(),
+        id: format!("day-{}", day.format("%Y-%m-%d")),
+        title: day_title(day),
+        weekday: weekday_context(day.weekday()),
+        label: day_label(input, day, events),
+        letters: events.iter().map(|event_index| event_letter(*event_index)).collect(),
+        events: events.to_vec(),
+        event: markers.last().copied(),
+        status: status_context(markers.last().map(|event_index| event_status(input, *event_index, day)).unwrap_or_default()),
+        statuses: events.iter().map(|event_index| status_context(event_status(input, *event_index, day))).collect(),
+        adjacent,
+        bars: bars.iter().map(|piece| piece.map(|piece| BarContext {
+            event: piece.event_index,
+            start: piece.start,
+            end: piece.end,
+            status: status_context(event_status(input, piece.event_index, day)),
+        })).collect(),
+        markers,
+        labels,
+    };
+}
+
+/// 1ヶ月分のカレンダー
+/// scheduleは前後の月の日付を表示するときに使う全体のschedule
+fn month_context<'a>(input: &'a Input, schedule: &[(NaiveDate, Vec<i32>)], schedule_monthly: &[(NaiveDate, Vec<i32>)], options: &RenderOptions) -> Result<MonthContext<'a>, Error> {
+    let (ref first_day, _) = schedule_monthly[0];
+    let offset = options.week_start.offset(first_day.weekday()) as i64;
+
+    let mut weeks = Vec::new();
+    let mut week_labels = Vec::new();
+    for (row, week) in split_weekly(schedule_monthly, options.week_start).iter().enumerate() {
+        // マス目の位置の日付(表示しないマス目はNone)
+        let cell_days: Vec<Option<NaiveDate>> = week.iter().enumerate().map(|(column, day)| match day {
+            Some((day, _)) => Some(*day),
+            None if options.adjacent_days => Some(*first_day + Duration::days((row * 7 + column) as i64 - offset)),
+            None => None,
+        }).collect();
+        // 帯で表示するイベント(マス目ごとの段)
+        let bars = if options.bars { week_bars(schedule, &cell_days) } else { vec![Vec::new(); 7] };
+
+        let mut days = Vec::new();
+        for (column, day) in week.iter().enumerate() {
+            days.push(match (day, cell_days[column]) {
+                (Some((day, events)), _) => Some(day_context(input, day, events, false, &bars[column], options)),
+                // 月の前後の空白には前後の月の日付を入れる
+                (None, Some(day)) => Some(day_context(input, &day, events_on(schedule, day), true, &bars[column], options)),
+                (None, None) => None,
+            });
+        }
+        weeks.push(days);
+
+        let week_first_day = *first_day + Duration::days((row * 7) as i64 - offset);
+        week_labels.push(week_label(input, week_first_day, options.week_numbers, options.week_start)?);
+    }
+
+    let mut agenda = Vec::new();
+    for (day, events) in schedule_monthly {
+        for event_index in events {
+            agenda.push(AgendaContext {
+                date: format!("{}/{}", day.month(), day.day()),
+                iso_date: day.format("%Y-%m-%d").to_string(),
+                weekday: weekday_context(day.weekday()),
+                event: event_context(input, *event_index as usize, options)?,
+                status: status_context(event_status(input, *event_index, day)),
+            });
+        }
+    }
+
+    return Ok(MonthContext {
+        name: format!("{:?}", month_name(first_day)?),
+        year: first_day.year(),
+        month: first_day.month(),
+        date: first_day.format("%Y-%m").to_string(),
+        weeks,
+        week_labels,
+        agenda,
+    });
+}
@@ -0,0 +1,327 @@
+use crate::structs::input::Input;
+use crate::schedule::{calc_calendar, split_monthly, split_weekly, month_name, event_letter, event_status};
+use crate::options::{Layout, RenderOptions, WeekStart};
+use crate::error::Error;
+
+use chrono::{NaiveDate, Weekday, Datelike};
+
+/// テキスト中でイベントを示すマーカーの種類
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Marker {
+    /// マーカーの色に合わせた絵文字(🟦など)
+    Emoji,
+    /// 脚注のようなアルファベット(A, B, ...)
+    Letter,
+}
+
+impl Marker {
+    /// コマンドライン引数の文字列からMarkerを得る
+    pub fn from_name(name: &str) -> Option<Marker> {
+        match name {
+            "emoji" => Some(Marker::Emoji),
+            "letter" => Some(Marker::Letter),
+            _ => None,
+        }
+    }
+
+    /// event_index番目のイベントのマーカー
+    fn to_string(self, input: &Input, event_index: i32, options: &RenderOptions) -> Result<String, Error> {
+        match self {
+            Marker::Emoji => Ok(options.event_pattern(input, event_index)?.emoji().to_string()),
+            Marker::Letter => Ok(event_letter(event_index)),
+        }
+    }
+}
+
+/// Markdownでカレンダーを出力する
+/// 月ごとのマス目は表に、一覧は箇条書きにする
+pub fn create_markdown(input: &Input, marker: Marker, options: &RenderOptions) -> Result<String, Error> {
+    let layout = options.layout;
+    let schedules_monthly = split_monthly(calc_calendar(input)?);
+    let mut markdown = format!("## {}\n", input.title);
+
+    if layout != Layout::Agenda {
+        for schedule_monthly in &schedules_monthly {
+            markdown = format!("{}\n### {}\n\n{}", markdown, month_title(schedule_monthly)?, markdown_table(input, schedule_monthly, marker, options)?);
+        }
+
+        // 凡例
+        markdown = format!("{}\n", markdown);
+        for (i, event) in input.events.iter().enumerate().filter(|(i, _)| input.is_shown(*i)) {
+            let separator = if marker == Marker::Letter { ":" } else { "" };
+            markdown = format!("{}- {}{} {}\n", markdown, marker.to_string(input, i as i32, options)?, separator, event.name);
+        }
+    }
+
+    if layout != Layout::Grid {
+        for schedule_monthly in &schedules_monthly {
+            let lines = agenda_lines(input, schedule_monthly);
+            // イベントのない月は出力しない
+            if lines.len() == 0 { continue; }
+
+            markdown = format!("{}\n### {}\n\n", markdown, month_title(schedule_monthly)?);
+            for line in lines {
+                markdown = format!("{}- {}\n", markdown, line.join(" "));
+            }
+        }
+    }
+
+    return Ok(markdown);
+}
+
+/// cal(1)のような等幅のテキストでカレンダーを出力する
+/// マーカーにはアルファベットを用い、凡例を添える
+pub fn create_plain(input: &Input, options: &RenderOptions) -> Result<String, Error> {
+    let layout = options.layout;
+    let schedules_monthly = split_monthly(calc_calendar(input)?);
+    let mut text = format!("{}\n", input.title);
+
+    if layout != Layout::Agenda {
+        for schedule_monthly in &schedules_monthly {
+            text = format!("{}\n{}", text, plain_table(schedule_monthly, options.week_start)?);
+        }
+
+        // 凡例
+        text = format!("{}\n", text);
+        for (i, event) in input.events.iter().enumerate().filter(|(i, _)| input.is_shown(*i)) {
+            text = format!("{}{}: {}\n", text, event_letter(i as i32), event.name);
+        }
+    }
+
+    if layout != Layout::Grid {
+        for schedule_monthly in &schedules_monthly {
+            let lines = agenda_lines(input, schedule_monthly);
+            // イベントのない月は出力しない
+            if lines.len() == 0 { continue; }
+
+            text = format!("{}\n{}\n", text, month_title(schedule_monthly)?);
+            for line in lines {
+                // 日付の列を揃える
+                text = format!("{}{:>7} {}\n", text, line[0], line[1..].join("  "));
+            }
+        }
+    }
+
+    return Ok(text);
+}
+
+/// 端末の色の対応状況
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    /// 色を付けない
+    None,
+    /// 256色
+    Ansi256,
+    /// 24bitカラー
+    TrueColor,
+}
+
+/// 端末に表示するためのカレンダーを出力する
+/// 色を付けられないときはcreate_plainと同じくアルファベットのマーカーを用いる
+pub fn create_ansi(input: &Input, color: ColorMode, options: &RenderOptions) -> Result<String, Error> {
+    if color == ColorMode::None {
+        let mut options = options.clone();
+        options.layout = Layout::Grid;
+        return create_plain(input, &options);
+    }
+
+    let schedules_monthly = split_monthly(calc_calendar(input)?);
+    let mut text = format!("{}\n", input.title);
+
+    for schedule_monthly in &schedules_monthly {
+        text = format!("{}\n{}\n", text, format!("{:^28}", month_title(schedule_monthly)?).trim_end());
+
+        // 曜日を週の始まり(week_start)から並べる
+        for weekday in options.week_start.weekdays().iter() {
+            let name = format!("{:?}", weekday);
+            text = format!("{}{} ", text, paint(&format!("{:>3}", &name[..2]), weekday_color(*weekday), None, color));
+        }
+        text = format!("{}\n", text.trim_end());
+
+        for week in split_weekly(schedule_monthly, options.week_start) {
+            let mut line = String::new();
+            for day in week {
+                let cell = match day {
+                    Some((day, events)) => {
+                        let number = format!("{:>3}", day.day());
+                        // 複数のイベントが重なる日は後に定義されたイベントで塗る
+                        match events.last() {
+                            Some(event_index) => {
+                                let colorcode = options.event_color(input, *event_index)?;
+                                paint(&number, Some("#FFFFFF"), Some(&colorcode), color)
+                            }
+                            None => paint(&number, weekday_color(day.weekday()), None, color),
+                        }
+                    }
+                    None => "   ".to_string(),
+                };
+                line = format!("{}{} ", line, cell);
+            }
+            text = format!("{}{}\n", text, line.trim_end());
+        }
+    }
+
+    // 凡例
+    text = format!("{}\n", text);
+    for (i, event) in input.events.iter().enumerate().filter(|(i, _)| input.is_shown(*i)) {
+        let colorcode = options.event_color(input, i as i32)?;
+        text = format!("{}{} {}\n", text, paint("  ", None, Some(&colorcode), color), event.name);
+    }
+
+    return Ok(text);
+}
+
+/// 曜日に応じた文字色(日曜は赤、土曜は青)
+fn weekday_color(weekday: Weekday) -> Option<&'static str> {
+    match weekday {
+        Weekday::Sun => Some("#F44336"),
+        Weekday::Sat => Some("#2196F3"),
+        _ => None,
+    }
+}
+
+/// 文字列をANSIエスケープシーケンスで色付けする
+/// fg, bgは"#2196F3"のようなカラーコード
+fn paint(text: &str, fg: Option<&str>, bg: Option<&str>, color: ColorMode) -> String {
+    let mut codes = Vec::new();
+    if let Some(fg) = fg { codes.push(format!("38;{}", ansi_color(fg, color))); }
+    if let Some(bg) = bg { codes.push(format!("48;{}", ansi_color(bg, color))); }
+    if codes.len() == 0 || color == ColorMode::None {
+        return text.to_string();
+    }
+
+    return format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text);
+}
+
+/// カラーコードをANSIの色指定に変換する
+fn ansi_color(colorcode: &str, color: ColorMode) -> String {
+    let hex = colorcode.trim_start_matches('#');
+    let rgb = u32::from_str_radix(hex, 16).unwrap_or(0);
+    let (r, g, b) = ((rgb >> 16) & 0xff, (rgb >> 8) & 0xff, rgb & 0xff);
+
+    match color {
+        ColorMode::TrueColor => format!("2;{};{};{}", r, g, b),
+        // 6x6x6のカラーキューブのうち最も近い色
+        _ => {
+            let cube = |c: u32| (c * 5 + 127) / 255;
+            format!("5;{}", 16 + 36 * cube(r) + 6 * cube(g) + cube(b))
+        }
+    }
+}
+
+/// 1ヶ月分のMarkdownの表
+fn markdown_table(input: &Input, schedule_monthly: &[(NaiveDate, Vec<i32>)], marker: Marker, options: &RenderOptions) -> Result<String, Error> {
+    let mut table = String::new();
+
+    // 曜日を週の始まり(week_start)から並べる
+    for weekday in options.week_start.weekdays().iter() {
+        table = format!("{}| {:?}. ", table, weekday);
+    }
+    table = format!("{}|\n{}|\n", table, "|:-:".repeat(7));
+
+    for week in split_weekly(schedule_monthly, options.week_start) {
+        for day in week {
+            let cell = match day {
+                Some((day, events)) => {
+                    let markers = events.iter().map(|event_index| marker.to_string(input, *event_index, options)).collect::<Result<Vec<String>, Error>>()?;
+                    match marker {
+                        Marker::Emoji => format!("{}{}", day.day(), markers.join("")),
+                        Marker::Letter if markers.len() > 0 => format!("{} ({})", day.day(), markers.join(",")),
+                        Marker::Letter => format!("{}", day.day()),
+                    }
+                }
+                None => String::new(),
+            };
+            table = format!("{}| {} ", table, cell);
+        }
+        table = format!("{}|\n", table);
+    }
+
+    return Ok(table);
+}
+
+/// 1ヶ月分のcal(1)のようなテキスト
+fn plain_table(schedule_monthly: &[(NaiveDate, Vec<i32>)], week_start: WeekStart) -> Result<String, Error> {
+    // マスの幅(日付2桁 + その日のマーカーをつなげた長さ + 区切り)
+    // マーカーは"AA"のように2文字以上になることがある
+    let max_markers = schedule_monthly.iter()
+        .map(|(_, events)| events.iter().map(|event_index| event_letter(*event_index).len()).sum::<usize>())
+        .max().unwrap_or(0).max(1);
+    let width = 2 + max_markers + 1;
+
+    // 月の名前を中央に寄せる
+    let mut text = format!("{:^w$}", month_title(schedule_monthly)?, w = width * 7);
+    text = format!("{}\n", text.trim_end());
+
+    // 曜日を週の始まり(week_start)から並べる
+    for weekday in week_start.weekdays().iter() {
+        let weekday = format!("{:?}", weekday);
+        text = format!("{}{:<w$}", text, &weekday[..2], w = width);
+    }
+    text = format!("{}\n", text.trim_end());
+
+    for week in split_weekly(schedule_monthly, week_start) {
+        let mut line = String::new();
+        for day in week {
+            let cell = match day {
+                Some((day, events)) => {
+                    let markers: Vec<String> = events.iter().map(|event_index| event_letter(*event_index)).collect();
+                    format!("{:>2}{}", day.day(), markers.join(""))
+                }
+                None => String::new(),
+            };
+            line = format!("{}{:<w$}", line, cell, w = width);
+        }
+        text = format!("{}{}\n", text, line.trim_end());
+    }
+
+    return Ok(text);
+}
+
+/// 1ヶ月分のイベントの一覧
+/// 各行は[日付, 曜日, イベント名, (時刻), (場所)]
+fn agenda_lines(input: &Input, schedule_monthly: &[(NaiveDate, Vec<i32>)]) -> Vec<Vec<String>> {
+    let mut lines = Vec::new();
+
+    for (day, events) in schedule_monthly {
+        for event_index in events {
+            let event = &input.events[*event_index as usize];
+            let mut line = vec![
+                format!("{}/{}", day.month(), day.day()),
+                format!("({:?}.)", day.weekday()),
+                event.name.clone(),
+            ];
+            if let Some(ref time) = event.time { line.push(time.clone()); }
+            if let Some(ref location) = event.location { line.push(format!("@ {}", location)); }
+            if let Some(label) = event_status(input, *event_index, day).label() { line.push(format!("({})", label)); }
+            lines.push(line);
+        }
+    }
+
+    return lines;
+}
+
+/// "April 2019"のような月の名前
+fn month_title(schedule_monthly: &[(NaiveDate, Vec<i32>)]) -> Result<String, Error> {
+    let (ref first_day, _) = schedule_monthly[0];
+    return Ok(format!("{:?} {}", month_name(first_day)?, first_day.year()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_table_fits_two_letter_markers() {
+        let day = |day| NaiveDate::from_ymd_opt(2019, 4, day).unwrap();
+        let schedule_monthly: Vec<(NaiveDate, Vec<i32>)> = (1..=30).map(|d| match d {
+            8 => (day(d), vec![0, 26]),
+            9 => (day(d), vec![27]),
+            _ => (day(d), vec![]),
+        }).collect();
+        let text = plain_table(&schedule_monthly, WeekStart::Sunday).unwrap();
+        let week = text.lines().find(|line| line.contains("AAA")).unwrap();
+        // 日付2桁 + "AAA" + 区切りで1マス6文字
+        assert_eq!(week, " 7     8AAA  9AB  10    11    12    13");
+    }
+}
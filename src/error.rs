@@ -0,0 +1,125 @@
+use std::fmt;
+use std::io;
+
+use crate::theme::Theme;
+
+use chrono::NaiveDate;
+
+/// カレンダーの生成中に発生するエラー
+#[derive(Debug)]
+pub enum Error {
+    /// ファイルの読み書きに失敗した
+    Io { path: String, source: io::Error },
+    /// 入力ファイルの内容がjsonとして不正
+    Parse(serde_json::Error),
+    /// 設定ファイルの内容がtomlとして不正
+    Config { path: String, source: toml::de::Error },
+    /// 存在しない日付(dayがNoneなら存在しない月)
+    InvalidDate { year: i32, month: u32, day: Option<u32> },
+    /// 表示する範囲の端(start, end)の値が不正
+    InvalidBound(String),
+    /// 表示する範囲の始まりが終わりより後
+    InvalidRange { start: NaiveDate, end: NaiveDate },
+    /// イベントが表示する範囲の始まり(start)より前にある
+    BeforeStart { event: String, date: NaiveDate, start: NaiveDate },
+    /// イベントが表示する範囲の終わり(end)より後にある
+    AfterEnd { event: String, date: NaiveDate, end: NaiveDate },
+    /// 学期(semester)の日付の指定が不正
+    InvalidSemester(String),
+    /// 授業週を表示するのに学期(semester)の指定がない
+    MissingSemester,
+    /// 今日の日付(today)の指定が不正
+    InvalidToday(String),
+    /// 存在しないカレンダー(calendars)のidが指定された
+    UnknownCalendar(String),
+    /// カレンダー(calendars)のidに使えない文字が含まれている
+    InvalidCalendarId(String),
+    /// カレンダー(calendars)のidが重複している
+    DuplicateCalendarId(String),
+    /// カレンダー(calendars)に存在しないイベントの名前が書かれている
+    UnknownCalendarEvent { calendar: String, event: String },
+    /// 存在しないテーマが指定された
+    UnknownTheme(String),
+    /// イベントの種類がマーカーのパターンの数を超えている
+    TooManyEvents { limit: usize, count: usize },
+    /// テンプレートが不正、または処理に失敗した
+    Template(minijinja::Error),
+    /// 画像などの出力に失敗した
+    Render(String),
+    /// コマンドラインの引数が不正
+    Usage { ja: String, en: String },
+}
+
+impl Error {
+    /// 日本語のメッセージ
+    pub fn message_ja(&self) -> String {
+        match self {
+            Error::Io { path, source } => format!("{}を正しく読み書きできませんでした: {}", path, source),
+            Error::Parse(err) => format!("ファイルの内容が不正です: {}", err),
+            Error::Config { path, source } => format!("設定ファイル{}の内容が不正です: {}", path, source),
+            Error::InvalidDate { year, month, day: Some(day) } => format!("{}年{}月{}日は存在しない日付です", year, month, day),
+            Error::InvalidDate { year, month, day: None } => format!("{}年{}月は存在しない月です", year, month),
+            Error::InvalidBound(bound) => format!("表示する範囲の指定{}が不正です(1から12の月か、該当年度の日付を指定してください)", bound),
+            Error::InvalidRange { start, end } => format!("表示する範囲の始まり({})が終わり({})より後です", start, end),
+            Error::BeforeStart { event, date, start } => format!("イベント{}の{}は表示する範囲の始まり({})より前です", event, date, start),
+            Error::AfterEnd { event, date, end } => format!("イベント{}の{}は表示する範囲の終わり({})より後です", event, date, end),
+            Error::InvalidSemester(date) => format!("学期の日付の指定{}が不正です(YYYY-MM-DDの形式で指定してください)", date),
+            Error::MissingSemester => "授業週を表示するには入力ファイルにsemesterを指定してください".to_string(),
+            Error::InvalidToday(today) => format!("今日の日付の指定{}が不正です(YYYY-MM-DDの形式で指定してください)", today),
+            Error::UnknownCalendar(id) => format!("カレンダー{}は入力ファイルのcalendarsにありません", id),
+            Error::InvalidCalendarId(id) => format!("カレンダーのid{}が不正です(英数字・-・_で指定してください)", id),
+            Error::DuplicateCalendarId(id) => format!("カレンダーのid{}が重複しています", id),
+            Error::UnknownCalendarEvent { calendar, event } => format!("カレンダー{}のイベント{}はeventsにありません", calendar, event),
+            Error::UnknownTheme(name) => format!("テーマ{}は存在しません({}から選んでください)", name, Theme::names()),
+            Error::TooManyEvents { limit, count } => format!("イベントの種類は{}つまでです(現在{}つ)", limit, count),
+            Error::Template(err) => format!("テンプレートの処理に失敗しました: {}", err),
+            Error::Render(reason) => format!("出力に失敗しました: {}", reason),
+            Error::Usage { ja, .. } => ja.clone(),
+        }
+    }
+
+    /// 英語のメッセージ
+    pub fn message_en(&self) -> String {
+        match self {
+            Error::Io { path, source } => format!("failed to read or write {}: {}", path, source),
+            Error::Parse(err) => format!("invalid input file: {}", err),
+            Error::Config { path, source } => format!("invalid config file {}: {}", path, source),
+            Error::InvalidDate { year, month, day: Some(day) } => format!("{:04}-{:02}-{:02} is not a valid date", year, month, day),
+            Error::InvalidDate { year, month, day: None } => format!("{:04}-{:02} is not a valid month", year, month),
+            Error::InvalidBound(bound) => format!("invalid range bound {} (expected a month from 1 to 12 or a date in the year)", bound),
+            Error::InvalidRange { start, end } => format!("the range starts ({}) after it ends ({})", start, end),
+            Error::BeforeStart { event, date, start } => format!("event {} on {} is before the start of the range ({})", event, date, start),
+            Error::AfterEnd { event, date, end } => format!("event {} on {} is after the end of the range ({})", event, date, end),
+            Error::InvalidSemester(date) => format!("invalid semester date {} (expected YYYY-MM-DD)", date),
+            Error::MissingSemester => "academic week numbers require a semester in the input".to_string(),
+            Error::InvalidToday(today) => format!("invalid date {} for today (expected YYYY-MM-DD)", today),
+            Error::UnknownCalendar(id) => format!("calendar {} is not in the calendars of the input", id),
+            Error::InvalidCalendarId(id) => format!("invalid calendar id {} (use letters, digits, - and _)", id),
+            Error::DuplicateCalendarId(id) => format!("calendar id {} is used more than once", id),
+            Error::UnknownCalendarEvent { calendar, event } => format!("event {} in calendar {} is not in the events", event, calendar),
+            Error::UnknownTheme(name) => format!("unknown theme {} (available: {})", name, Theme::names()),
+            Error::TooManyEvents { limit, count } => format!("at most {} kinds of events are supported (got {})", limit, count),
+            Error::Template(err) => format!("failed to render the template: {}", err),
+            Error::Render(reason) => format!("failed to render: {}", reason),
+            Error::Usage { en, .. } => en.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\n{}", self.message_ja(), self.message_en())
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Parse(err) => Some(err),
+            Error::Config { source, .. } => Some(source),
+            Error::Template(err) => Some(err),
+            _ => None,
+        }
+    }
+}
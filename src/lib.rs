@@ -0,0 +1,72 @@
+#![allow(clippy::needless_return, clippy::len_zero, clippy::upper_case_acronyms)]
+
+//! ACSCG - AmCr新歓カレンダージェネレーター
+//!
+//! json形式のデータ(Input)から新歓カレンダーのhtmlなどを生成する
+
+extern crate chrono;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+extern crate num_traits;
+extern crate num_derive;
+extern crate resvg;
+extern crate toml;
+extern crate minijinja;
+
+mod structs;
+mod error;
+pub(crate) mod schedule;
+pub(crate) mod options;
+pub(crate) mod theme;
+pub(crate) mod config;
+pub(crate) mod create_html;
+pub(crate) mod create_template;
+pub mod create_svg;
+pub mod create_png;
+pub mod create_text;
+
+pub use crate::structs::input::{Input, Calendar, Semester, Break};
+pub use crate::structs::input::event::{Event, Date, Status};
+pub use crate::structs::web::ToHtml;
+pub use crate::structs::web::element::Element;
+pub use crate::structs::web::css::{CSS, Media, MakerCSSs};
+pub use crate::structs::date::MonthNames;
+pub use crate::error::Error;
+pub use crate::config::{Config, Locale};
+pub use crate::theme::Theme;
+pub use crate::options::{Layout, Paper, PrintOptions, WeekStart, WeekNumbers, CellMode, ColorScheme, Popover, RenderOptions, RenderOptionsBuilder, MAX_EVENTS_PER_CATEGORY};
+pub use crate::schedule::calc_calendar;
+pub use crate::create_template::DEFAULT_TEMPLATE;
+
+/// Inputからカレンダーのhtmlを生成する
+pub fn render(input: &Input, options: &RenderOptions) -> Result<String, Error> {
+    check_events(input, options)?;
+
+    // テンプレートが指定されているときはテンプレートに従って、なければ組み込みのテンプレートで出力する
+    if let Some(ref path) = options.template {
+        return create_template::create_from_file(input, path, options);
+    }
+
+    return create_template::create(input, "calendar.html", create_template::DEFAULT_TEMPLATE, options);
+}
+
+/// イベントの種類がマーカーのパターンの数を超えていないか確かめる
+pub fn check_events(input: &Input, options: &RenderOptions) -> Result<(), Error> {
+    let limit_of_events = options.max_events();
+
+    // 同じカテゴリのイベントは1つの色の濃淡を使うので、1種類として数える
+    let families = input.color_families();
+    let count = families.len();
+    if count > limit_of_events {
+        return Err(Error::TooManyEvents { limit: limit_of_events, count });
+    }
+
+    // 1つのカテゴリのイベントは濃淡の数まで
+    if let Some(family) = families.iter().find(|family| family.len() > options::MAX_EVENTS_PER_CATEGORY) {
+        return Err(Error::TooManyEvents { limit: options::MAX_EVENTS_PER_CATEGORY, count: family.len() });
+    }
+
+    return Ok(());
+}
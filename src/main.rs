@@ -1,19 +1,14 @@
-extern crate chrono;
-extern crate serde;
-#[macro_use]
-extern crate serde_derive;
-extern crate serde_json;
-extern crate num_traits;
-extern crate num_derive;
+#![allow(clippy::needless_return, clippy::len_zero)]
 
-mod structs;
+extern crate acscg;
+extern crate chrono;
 
 use std::env;
+use std::process;
 use std::fs::File;
-use std::io::{self, Read, Write, BufWriter};
-use crate::structs::input::Input;
-use crate::structs::web::css::MakerCSSs;
-use num_traits::FromPrimitive;
+use std::io::{self, Write, BufWriter, IsTerminal};
+use acscg::{ColorScheme, Config, Error, Input, Layout, Paper, Popover, Theme, WeekStart, WeekNumbers, CellMode, create_svg, create_png, create_text, DEFAULT_TEMPLATE};
+use chrono::Datelike;
 
 fn main() {
     // 実行ファイルのpath
@@ -21,557 +16,381 @@ fn main() {
     // 実行時引数
     let args: Vec<String> = env::args().skip(1).collect();
     if args.len() == 0 {
-        println!("Illegal Arguments");
-        return;
+        eprintln!("{}", illegal_arguments());
+        process::exit(1);
     }
-    // 入力ファイルへのpath
-    let path = &args[0];
-    // 入力ファイルの内容
-    let input = std::fs::read_to_string(path).expect("ファイルを正しく読み込めませんでした");
-    // struct Input化した入力ファイル
-    let input: Input = serde_json::from_str(&input).expect("ファイルの内容が不正です");
 
-    let mut limit_of_events = 0;
-
-    loop {
-        match MakerCSSs::from_i32(limit_of_events) {
-            None => break,
-            _ => { limit_of_events += 1; }
+    // 設定ファイルの読み込み
+    let config = match Config::load(config_path(&args)) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
         }
+    };
+
+    // サブコマンドの振り分け
+    let result = match args[0].as_str() {
+        "export" => export(&args[1..], &config),
+        "preview" => preview(&args[1..], &config),
+        "config" => show_config(&args[1..], &config),
+        "template" => show_template(&args[1..]),
+        _ => generate(&args, &config),
+    };
+
+    if let Err(err) = result {
+        eprintln!("{}", config.error_message(&err));
+        process::exit(1);
     }
-    if input.events.len() > limit_of_events as usize {
-        println!("イベントの種類は{}つまでです", limit_of_events);
-        return;
-    }
-
-    // html生成
-    let html = create_html::create(input);
-
-
-    let file =
-        // 出力ファイル名の指定を受けているとき
-        if args.len() >= 2 {
-            File::create(&args[1]).expect("Unable to create file")
-        } else {
-            File::create("calendar.html").unwrap()
-        };
-    let mut buf = BufWriter::new(file);
-    buf.write(html.as_bytes()).unwrap();
-    buf.flush().unwrap();
 }
 
-pub mod create_html {
-    use crate::structs::web::{ToHtml, element::Element, css::CSS};
-    use crate::structs::web::css::MakerCSSs;
-    use crate::structs::input::Input;
-    use crate::structs::date::MonthNames;
-
-    use chrono::{NaiveDate, Weekday, Datelike};
-    use num_traits::FromPrimitive;
+/// --configで指定された設定ファイルのpath
+fn config_path(args: &[String]) -> Option<&str> {
+    let index = args.iter().position(|arg| arg == "--config")?;
+    return args.get(index + 1).map(|path| path.as_str());
+}
 
-    /// Input構造体(インプットされたファイルの中身)を受け取って、
-    /// それに応じたカレンダーのhtmlを出力する
-    pub fn create(input: Input) -> String {
-        // js -> document
-        let mut document = Element::create("html");
+/// 設定ファイルの項目に対応するオプションを解釈してconfigに反映する
+/// generate, export, preview, config showで共通に使い、設定ファイルの値をコマンドラインで上書きできるようにする
+/// argが共通のオプションでなければfalseを、値が不正ならエラーを返す
+fn parse_common_option(arg: &str, iter: &mut std::slice::Iter<String>, config: &mut Config) -> Result<bool, Error> {
+    match arg {
+        "--config" => {
+            // 設定ファイルはmainで読み込み済み
+            iter.next().ok_or_else(|| usage("--configには設定ファイルのpathを指定してください", "--config expects the path of a config file"))?;
+        }
+        "--calendar" => {
+            config.calendar = Some(iter.next().ok_or_else(|| usage("--calendarには入力ファイルのcalendarsのidを指定してください", "--calendar expects the id of a calendar in the input"))?.clone());
+        }
+        "--layout" => {
+            config.render.layout = iter.next().and_then(|name| Layout::from_name(name))
+                .ok_or_else(|| invalid_choice("--layout", "grid, agenda, both"))?;
+        }
+        "--week-start" => {
+            config.render.week_start = iter.next().and_then(|name| WeekStart::from_name(name))
+                .ok_or_else(|| invalid_choice("--week-start", "sunday, monday"))?;
+        }
+        "--theme" => {
+            let theme = iter.next().and_then(|name| Theme::from_name(name))
+                .ok_or_else(|| invalid_choice("--theme", "light, dark, high-contrast, club-brand"))?;
+            config.render.theme = theme.name.to_string();
+        }
+        "--color-scheme" => {
+            config.render.color_scheme = iter.next().and_then(|name| ColorScheme::from_name(name))
+                .ok_or_else(|| invalid_choice("--color-scheme", "auto, light, dark"))?;
+        }
+        "--adjacent-days" => config.render.adjacent_days = true,
+        "--no-adjacent-days" => config.render.adjacent_days = false,
+        "--bars" => config.render.bars = true,
+        "--no-bars" => config.render.bars = false,
+        "--cells" => {
+            config.render.cells = iter.next().and_then(|name| CellMode::from_name(name))
+                .ok_or_else(|| invalid_choice("--cells", "compact, detailed"))?;
+        }
+        "--popover" => {
+            config.render.popover = iter.next().and_then(|name| Popover::from_name(name))
+                .ok_or_else(|| invalid_choice("--popover", "modal, details, none"))?;
+        }
+        "--week-numbers" => {
+            config.render.week_numbers = iter.next().and_then(|name| WeekNumbers::from_name(name))
+                .ok_or_else(|| invalid_choice("--week-numbers", "none, iso, academic"))?;
+        }
+        "--today" => {
+            config.render.today = Some(iter.next().ok_or_else(|| usage("--todayには今日として扱う日付をYYYY-MM-DDの形式で指定してください", "--today expects a date in YYYY-MM-DD"))?.clone());
+        }
+        "--template" => {
+            config.render.template = Some(iter.next().ok_or_else(|| usage("--templateにはテンプレートファイルのpathを指定してください", "--template expects the path of a template file"))?.clone());
+        }
+        "--css" => {
+            config.render.css_file = Some(iter.next().ok_or_else(|| usage("--cssにはcssファイルのpathを指定してください", "--css expects the path of a css file"))?.clone());
+        }
+        "--print" => config.render.print = Some(config.render.print.unwrap_or_default()),
+        "--no-print" => config.render.print = None,
+        "--paper" => {
+            // --paperの指定は印刷モードを伴う
+            let mut print = config.render.print.unwrap_or_default();
+            print.paper = iter.next().and_then(|name| Paper::from_name(name))
+                .ok_or_else(|| invalid_choice("--paper", "a4, a3"))?;
+            config.render.print = Some(print);
+        }
+        "--months-per-page" => {
+            // --months-per-pageの指定は印刷モードを伴う
+            let mut print = config.render.print.unwrap_or_default();
+            print.months_per_page = iter.next().and_then(|n| n.parse::<u32>().ok()).filter(|n| *n > 0)
+                .ok_or_else(|| usage("--months-per-pageには1以上の整数を指定してください", "--months-per-page expects an integer of 1 or more"))?;
+            config.render.print = Some(print);
+        }
+        _ => return Ok(false),
+    }
 
-        // head領域を追加
-        let head = create_head(&input);
-        document.append(head);
+    return Ok(true);
+}
 
-        // body領域を追加
-        let mut body = create_body(&input);
-        document.append(body);
+/// オプションでない引数(入力・出力ファイル名)を受け取る
+/// 知らないオプション(--で始まる引数)をファイル名として扱わないようにエラーにする
+fn positional_arg(arg: &String) -> Result<&String, Error> {
+    if arg.starts_with("--") {
+        return Err(usage(&format!("{}は存在しないオプションです", arg), &format!("unknown option {}", arg)));
+    }
 
-        // style領域を追加
-        let mut style = create_style(&input);
-        document.append(style);
+    return Ok(arg);
+}
 
-        return document.to_html_index_noted(0);
+/// カレンダーのhtmlを生成する
+/// ACSCG [input] [output] [options]
+fn generate(args: &[String], config: &Config) -> Result<(), Error> {
+    // オプションとそれ以外の引数を分ける
+    let mut config = config.clone();
+    let mut positional: Vec<&String> = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if !parse_common_option(arg, &mut iter, &mut config)? {
+            positional.push(positional_arg(arg)?);
+        }
     }
-
-    /// html::head領域を作成する
-    fn create_head(input: &Input) -> Element {
-        let mut head = Element::create("head");
-
-        // title要素
-        let mut title = Element::create("title");
-        title.set_text(&input.title);
-
-        // materialize css :css
-        let mut materializecss_css = Element::create("link");
-        materializecss_css.set_attribute("rel", "stylesheet");
-        materializecss_css.set_attribute("href", "https://cdnjs.cloudflare.com/ajax/libs/materialize/1.0.0/css/materialize.min.css");
-
-        // materialize css :js
-        let mut materializecss_js = Element::create("script");
-        materializecss_js.set_attribute("src", "https://cdnjs.cloudflare.com/ajax/libs/materialize/1.0.0/js/materialize.min.js");
-
-        // Material icons
-        let mut materialicons = Element::create("link");
-        materialicons.set_attribute("href", "https://fonts.googleapis.com/icon?family=Material+Icons");
-        materialicons.set_attribute("rel", "stylesheet");
-
-        // todo remove
-//        let mut custom = Element::create("link");
-//        custom.set_attribute("rel", "stylesheet");
-//        custom.set_attribute("href", "custom.css");
-
-        // append to head
-        head.append(title);
-        head.append(materializecss_css);
-        head.append(materializecss_js);
-        head.append(materialicons);
-//        head.append(custom);
-
-        return head;
+    if positional.len() == 0 {
+        return Err(illegal_arguments());
     }
 
-    /// html::body領域を作成する
-    fn create_body(input: &Input) -> Element {
-        let mut body = Element::create("body");
-
-        // nav領域を追加
-        let nav = create_nav(input);
-        body.append(nav);
-
-        // main領域を追加
-        let mut main = create_main(input);
-        body.append(main);
-
-        return body;
+    let input = Input::from_file(positional[0])?;
+
+    // 出力ファイル名の指定を受けているとき
+    let output = match (positional.get(1), &config.output) {
+        (Some(output), _) => output.as_str(),
+        (None, Some(output)) => output.as_str(),
+        (None, None) => "calendar.html",
+    };
+
+    // calendarsがあって--calendarの指定がないときは、カレンダーごとに別々のファイルに出力する
+    if config.calendar.is_none() && input.calendars.len() > 0 {
+        // calendar.html -> calendar-all.html, calendar-freshman.html, ...
+        let (stem, extension) = match output.rfind('.') {
+            Some(dot) => (&output[..dot], &output[dot..]),
+            None => (output, ""),
+        };
+        for calendar in &input.calendars {
+            let html = acscg::render(&input.calendar(&calendar.id)?, &config.render)?;
+            write_file(&format!("{}-{}{}", stem, calendar.id, extension), html.as_bytes())?;
+        }
+        return Ok(());
     }
 
-    /// html::body::nav領域を作成する
-    fn create_nav(input: &Input) -> Element {
-        // navigation barのタイトル(ページ最上中央)
-        let mut a = Element::create("a");
-        a.add_class("brand-logo center");
-        a.set_text(&input.title);
-
-        // タイトルのwrapper
-        let mut div = Element::create("div");
-        div.add_class("nav-wrapper");
-        div.append(a);
+    // html生成
+    let input = select_calendar(input, &config)?;
+    let html = acscg::render(&input, &config.render)?;
+    return write_file(output, html.as_bytes());
+}
 
-        // nav本体
-        let mut nav = Element::create("nav");
-        nav.append(div);
+/// カレンダーを画像として書き出す
+/// ACSCG export [input] [output] --format svg|png|markdown|text [options]
+fn export(args: &[String], config: &Config) -> Result<(), Error> {
+    let mut config = config.clone();
+    let mut format = String::from("svg");
+    let mut size = create_svg::Size::default();
+    let mut split = false;
+    let mut font: Option<String> = None;
+    let mut marker = create_text::Marker::Emoji;
+    let mut positional: Vec<&String> = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if parse_common_option(arg, &mut iter, &mut config)? {
+            continue;
+        }
 
-        return nav;
+        if arg == "--format" {
+            format = match iter.next() {
+                Some(format) if ["svg", "png", "markdown", "text"].contains(&format.as_str()) => format.clone(),
+                _ => {
+                    return Err(invalid_choice("--format", "svg, png, markdown, text"));
+                }
+            };
+        } else if arg == "--size" {
+            size = match iter.next().and_then(|name| create_svg::Size::from_name(name)) {
+                Some(size) => size,
+                None => {
+                    return Err(usage("--sizeは 1080x1350 のように指定してください", "--size expects a size like 1080x1350"));
+                }
+            };
+        } else if arg == "--split" {
+            split = true;
+        } else if arg == "--marker" {
+            marker = match iter.next().and_then(|name| create_text::Marker::from_name(name)) {
+                Some(marker) => marker,
+                None => {
+                    return Err(invalid_choice("--marker", "emoji, letter"));
+                }
+            };
+        } else if arg == "--font" {
+            font = match iter.next() {
+                Some(path) => Some(path.clone()),
+                None => {
+                    return Err(usage("--fontにはフォントファイルのpathを指定してください", "--font expects the path of a font file"));
+                }
+            };
+        } else {
+            positional.push(positional_arg(arg)?);
+        }
     }
-
-    /// html::body::main領域を作成する
-    fn create_main(input: &Input) -> Element {
-        let mut main = Element::create("main");
-
-        // header領域を追加
-        let header = create_header(input);
-        main.append(header);
-
-        // calendar領域を追加
-        let calendar = create_calendar(input);
-        main.append(calendar);
-
-        return main;
+    if positional.len() == 0 {
+        return Err(illegal_arguments());
     }
 
-    /// html::body::main::header領域を作成する
-    fn create_header(input: &Input) -> Element {
-        let mut header = Element::create("header");
-        let mut div = Element::create("div");
-        div.add_class("event-description");
-
-        // イベントの説明一覧
-        let mut ul = Element::create("ul");
-        ul.add_class("collection");
-
-        for i in 0..input.events.len() {
-            // イベントの説明
-            let mut li = Element::create("li");
-            li.add_class("collection-item");
-            // カレンダー中でイベントを示すマーカーのサンプル
-            let mut span_marker = Element::create("span");
-            span_marker.set_text(&"10".to_string());
-            span_marker.set_attribute("event_index", &format!("{}", i));
-            span_marker.add_class("circled");
-            // イベントの名前
-            let mut span_description = Element::create("span");
-            span_description.set_text(&input.events[i].name);
-            span_description.add_class("description");
-            // liにspanをそれぞれ追加
-            li.append(span_marker);
-            li.append(span_description);
-            // ulにliを追加
-            ul.append(li);
+    let input = load_input(positional[0], &config)?;
+    let options = config.render;
+    acscg::check_events(&input, &options)?;
+
+    let extension = match format.as_str() {
+        "markdown" => "md",
+        "text" => "txt",
+        format => format,
+    };
+    let default_output = format!("calendar.{}", extension);
+    let output = if positional.len() >= 2 { positional[1].as_str() } else { default_output.as_str() };
+
+    // テキストは1つのファイルにまとめて出力する
+    if format == "markdown" || format == "text" {
+        if split {
+            return Err(usage("--splitは svg, png のときのみ指定できます", "--split is only available for svg and png"));
         }
-
-        // divに追加
-        div.append(ul);
-        // headerに追加
-        header.append(div);
-
-        return header;
+        let text = if format == "markdown" {
+            create_text::create_markdown(&input, marker, &options)?
+        } else {
+            create_text::create_plain(&input, &options)?
+        };
+        return write_file(output, text.as_bytes());
     }
 
-    /// html::body::main::calendars領域を作成する
-    fn create_calendar(input: &Input) -> Element {
-        let mut calendars = Element::create("div");
-        calendars.add_class("calendars");
-
-        let schedule = calc_calendar(input);
-
-        // scheduleを月ごとに分ける
-        let mut schedules_monthly: Vec<Vec<(NaiveDate, Option<i32>)>> = Vec::new();
-        let mut m = 0;
-        let mut index = 0;
-        for sch in schedule {
-            let (day, event) = sch;
-            if m == 0 {
-                m = day.month();
-                schedules_monthly.push(Vec::new());
-            } else if m != day.month() {
-                index += 1;
-                m = day.month();
-                schedules_monthly.push(Vec::new());
-            }
-            schedules_monthly[index].push((day, event));
+    if split {
+        // calendar.svg -> calendar-04.svg, calendar-05.svg, ...
+        let (stem, extension) = match output.rfind('.') {
+            Some(dot) => (&output[..dot], &output[dot..]),
+            None => (output, ""),
+        };
+        for (first_day, svg) in create_svg::create_monthly(&input, size, &options)? {
+            let path = format!("{}-{:02}{}", stem, first_day.month(), extension);
+            write_image(&path, &format, &svg, font.as_deref())?;
         }
+        return Ok(());
+    }
 
-        // scheduleを月ごとに処理する
-        for schedule_monthly in schedules_monthly {
-            let mut calendar = Element::create("div");
-            calendar.add_class("calendar");
-
-            // 月の名前を取得
-            let (ref first_day, _) = schedule_monthly[0];
-            let month_name = format!("{:?}", MonthNames::from_u32(first_day.month0()));
-
-            // calendar-title領域を追加
-            let title = create_calendar_title(month_name, input.year);
-//            println!("{}", &title.to_string());
-            calendar.append(title);
-
-            let table = create_calendar_table(&schedule_monthly);
-            calendar.append(table);
+    return write_image(output, &format, &create_svg::create(&input, size, &options)?, font.as_deref());
+}
 
-            // 格納
-            calendars.append(calendar);
+/// カレンダーを端末に表示する
+/// ACSCG preview [input] [options]
+fn preview(args: &[String], config: &Config) -> Result<(), Error> {
+    let mut config = config.clone();
+    let mut positional: Vec<&String> = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if !parse_common_option(arg, &mut iter, &mut config)? {
+            positional.push(positional_arg(arg)?);
         }
-
-        return calendars;
     }
-
-    /// html::body::main::calendars::calendar::calendar-title領域を作成する
-    fn create_calendar_title(month: String, year: i32) -> Element {
-        let mut title = Element::create("div");
-        title.add_class("calendar-title row");
-
-        // calendarをhtmlに変換
-        {
-            // icon left
-            {
-                // wrapper
-                let mut div = Element::create("div");
-                div.add_class("col s2");
-
-                // icon
-                let mut i = Element::create("i");
-                i.add_class("material-icons");
-                i.set_text(&"navigate_before".to_string());
-
-                // wrapperにiconを追加
-                div.append(i);
-
-                // iconのwrapperをtitleに追加
-                title.append(div);
-            }
-
-            // title本体
-            {
-                // wrapper
-                let mut div = Element::create("div");
-                div.add_class("col s8 center-align date");
-
-                let mut span_month = Element::create("span");
-                span_month.add_class("month");
-                span_month.set_text(&month);
-
-                let br = Element::create("br");
-
-                let mut span_year = Element::create("span");
-                span_year.add_class("year");
-                span_year.set_text(&format!("{}", year));
-
-                div.append(span_month);
-                div.append(br);
-                div.append(span_year);
-
-                title.append(div);
-            }
-
-            // icon right
-            {
-                // wrapper
-                let mut div = Element::create("div");
-                div.add_class("col s2");
-
-                // icon
-                let mut i = Element::create("i");
-                i.add_class("material-icons");
-                i.set_text(&"navigate_next".to_string());
-
-                // wrapperにiconを追加
-                div.append(i);
-
-                // iconのwrapperをtitleに追加
-                title.append(div);
-            }
-        }
-
-        return title;
+    if positional.len() == 0 {
+        return Err(illegal_arguments());
     }
 
-    /// html::body::main::calendars::calendar::table領域を作成する
-    fn create_calendar_table(schedule_monthly: &Vec<(NaiveDate, Option<i32>)>) -> Element {
-        let mut table = Element::create("table");
-        table.add_class("calendar-body");
-
-        // table headを整備する
-        {
-            let mut thead = Element::create("thead");
-            let mut tr = Element::create("tr");
-            // 曜日を日曜日からthに入れていく
-            for i in 0..7 {
-                let mut th = Element::create("th");
-
-                th.set_text(&format!("{:?}.", Weekday::from_i32((i + 6) % 7).unwrap()));
-
-                th.add_class("center-align");
-                // 日曜は赤、土曜は青
-                if i == 0 { th.add_class("red-text"); }
-                if i == 6 { th.add_class("blue-text"); }
-
-                // 列に追加
-                tr.append(th);
-            }
-            // theadにtrを格納
-            thead.append(tr);
-            // tableにtheadを格納
-            table.append(thead);
+    let input = load_input(positional[0], &config)?;
+    let options = config.render;
+    acscg::check_events(&input, &options)?;
+
+    // 出力先が端末でないとき、またはNO_COLORが設定されているときは色を付けない
+    let color = if !io::stdout().is_terminal() || env::var_os("NO_COLOR").is_some() {
+        create_text::ColorMode::None
+    } else {
+        match env::var("COLORTERM") {
+            Ok(ref colorterm) if colorterm == "truecolor" || colorterm == "24bit" => create_text::ColorMode::TrueColor,
+            _ => create_text::ColorMode::Ansi256,
         }
+    };
 
-        // table bodyを整備する
-        // カレンダーのマス目の左上から埋めていく
-        {
-            let mut tbody = Element::create("tbody");
-            // scheduleのindex
-            let mut index = 0;
-            // 行
-            for i in 0..5 {
-                let mut tr = Element::create("tr");
-                // 列
-                for j in 0..7 {
-                    let mut td = Element::create("td");
-                    td.add_class("center-align");
-                    // 日曜は赤、土曜は青
-                    if j == 0 { td.add_class("red-text"); }
-                    if j == 6 { td.add_class("blue-text"); }
-
-                    // 日付の出力を開始する?
-                    if index == schedule_monthly.len() {
-                        // schedule_monthly[index]がOutBoundsOfIndexになるのを防ぐ
-                    } else {
-                        let (ref day, ref eve) = schedule_monthly[index];
-                        let weekday = day.weekday();
-
-                        if Weekday::from_i32((j + 6) % 7).unwrap() == weekday {
-                            // 日付を出力する
-                            let mut span = Element::create("span");
-                            span.set_text(&format!("{}", index + 1));
-                            // イベントがある日を出力したとき
-                            if let Some(event_index) = eve {
-                                span.set_attribute("event_index", &format!("{}", event_index));
-                                span.add_class("circled");
-                            }
-
-                            td.append(span);
-                            index += 1;
-                        } else {
-                            // 何もしない
-                        }
-                    }
-
-                    // trにtdを格納
-                    tr.append(td);
-                }
-                // tbodyにtrを格納
-                tbody.append(tr);
-            }
-            table.append(tbody);
-        }
+    print!("{}", create_text::create_ansi(&input, color, &options)?);
+
+    return Ok(());
+}
 
-        return table;
+/// 設定を表示する
+/// ACSCG config show [options]
+fn show_config(args: &[String], config: &Config) -> Result<(), Error> {
+    if args.first().map(|arg| arg.as_str()) != Some("show") {
+        return Err(illegal_arguments());
     }
 
-    /// コンピュータ上にカレンダーを再現する
-    fn calc_calendar(input: &Input) -> Vec<(NaiveDate, Option<i32>)> {
-        // 何月から何月までのcalendarを作成する必要があるのかを探る
-        let mut min_month = 12;
-        let mut max_month = 1;
-
-        for event in &input.events {
-            for date in &event.dates {
-                let month = date.month;
-                if month > max_month { max_month = month; }
-                if month < min_month { min_month = month; }
-            }
+    // コマンドライン引数で上書きした結果を表示する
+    let mut config = config.clone();
+    let mut iter = args[1..].iter();
+    while let Some(arg) = iter.next() {
+        if !parse_common_option(arg, &mut iter, &mut config)? {
+            return Err(illegal_arguments());
         }
+    }
 
-        // 必要な月を出力
-        let mut day = NaiveDate::from_ymd(input.year, min_month, 1);
-        let the_day_after_last_day = NaiveDate::from_ymd(input.year, max_month + 1, 1);
-        // (日時,イベントid)
-        let mut schedules: Vec<(NaiveDate, Option<i32>)> = Vec::new();
-
-        // カレンダーに出力されるdayをvecにしまっておく
-        while day != the_day_after_last_day {
-            schedules.push((day.clone(), None));
-            day = day.succ();
-        }
+    print!("{}", config.to_toml()?);
 
-        // イベントとcalendar_vec内のNativeDateを紐付ける
-        // iはイベントindex
-        for i in 0..input.events.len() {
-            let event = &input.events[i];
-
-            // イベント開催日の配列
-            let mut event_dates: Vec<NaiveDate> = Vec::new();
-            for j in 0..event.dates.len() {
-                let date = &event.dates[j];
-                for k in 0..date.days.len() {
-                    let day = date.days[k];
-                    event_dates.push(NaiveDate::from_ymd(input.year, date.month, day));
-                }
-            }
-
-            // 総当たりでイベント開催日とカレンダーをマッチング
-            for j in 0..schedules.len() {
-                let (day, _) = schedules[j];
-                for k in 0..event_dates.len() {
-                    // match
-                    if day == event_dates[k] {
-                        schedules.remove(j);
-                        schedules.insert(j, (event_dates[k], Some(i as i32)));
-                    }
-                }
-            }
-        }
+    return Ok(());
+}
 
-        return schedules;
+/// 組み込みのテンプレートを表示する
+/// ACSCG template show
+fn show_template(args: &[String]) -> Result<(), Error> {
+    if args.first().map(|arg| arg.as_str()) != Some("show") {
+        return Err(illegal_arguments());
     }
 
-    /// style領域(css)を追加
-    fn create_style(input: &Input) -> Element {
-        let mut styles = Element::create("div");
-        styles.add_class("styles");
-
-        let mut style_static = create_style_static();
-        styles.append(style_static);
+    print!("{}", DEFAULT_TEMPLATE);
 
-        let mut style_dynamic = create_style_dynamic(input);
-        styles.append(style_dynamic);
+    return Ok(());
+}
 
-        return styles;
-    }
+/// コマンドラインの引数が不正であることを示すエラー
+fn usage(ja: &str, en: &str) -> Error {
+    return Error::Usage { ja: ja.to_string(), en: en.to_string() };
+}
 
-    /// CSSのうち入力(input)によって変化しない部分を出力する
-    fn create_style_static() -> Element {
-        let mut css_vec: Vec<CSS> = Vec::new();
-
-        let mut css = CSS::create("nav");
-        css.push_declaration("box-shadow", "none");
-        css.push_declaration("font-family", "Menlo");
-        css_vec.push(css);
-
-        let mut css = CSS::create("header");
-        css.push_declaration("padding", "20px 0");
-        css_vec.push(css);
-
-        let mut css = CSS::create(".event-description");
-        css.push_declaration("margin", "0 20px");
-        css.push_declaration("padding", "10px 0");
-        css.push_declaration("background-color", "rgb(244,245,246)");
-        css.push_declaration("border-left", "#ee6e73 solid 3px");
-        css_vec.push(css);
-
-        let mut css = CSS::create(".event-description ul.collection, .event-description ul.collection li.collection-item");
-        css.push_declaration("border", "none");
-        css.push_declaration("background-color", "inherit");
-        css_vec.push(css);
-
-        let mut css = CSS::create(".calendar-title");
-        css.push_declaration("width", "88vw");
-        css_vec.push(css);
-
-        let mut css = CSS::create(".calendar-title i");
-        css.push_declaration("font-size", "80px");
-        css.push_declaration("color", "#e0e0e0");
-        css_vec.push(css);
-
-        let mut css = CSS::create(".calendar-title .date");
-        css.push_declaration("padding", "10px 0");
-        css.push_declaration("color", "#757575");
-        css_vec.push(css);
-
-        let mut css = CSS::create(".calendar-title .date .month");
-        css.push_declaration("font-size", "25px");
-        css_vec.push(css);
-
-        let mut css = CSS::create(".circled");
-        css.push_declaration("padding", "10px");
-        css.push_declaration("border-radius", "5px");
-        css_vec.push(css);
-
-        let mut css = CSS::create(".circled.digit");
-        css.push_declaration("padding-right", "14px");
-        css.push_declaration("padding-left", "14px");
-        css_vec.push(css);
-
-        // todo remove non-static
-        let mut css = CSS::create(".circled.red, .circled.blue");
-        css.push_declaration("color", "white");
-        css_vec.push(css);
-
-        let mut style = String::new();
-        for static_css in css_vec {
-            style = format!("{}{}", style, static_css.to_html());
-        }
+/// オプションの値が選択肢にないことを示すエラー
+fn invalid_choice(option: &str, choices: &str) -> Error {
+    return usage(&format!("{}には {} のいずれかを指定してください", option, choices), &format!("{} expects one of {}", option, choices));
+}
 
-        let mut css = Element::create("style");
-        css.set_text(&style);
+/// 引数が足りないなど、引数の並びが不正であることを示すエラー
+fn illegal_arguments() -> Error {
+    return usage("引数が正しくありません", "Illegal Arguments");
+}
 
-        return css;
+/// svgを指定の形式で書き出す
+fn write_image(path: &str, format: &str, svg: &str, font: Option<&str>) -> Result<(), Error> {
+    if format == "png" {
+        return write_file(path, &create_png::create(svg, font)?);
     }
 
-    /// CSSのうち入力(input)によって変化する部分を出力する
-    fn create_style_dynamic(input: &Input) -> Element {
-        let mut css = Element::create("style");
+    return write_file(path, svg.as_bytes());
+}
 
-        let mut css_vec: Vec<CSS> = Vec::new();
+/// 入力ファイルを読み込んでInputにする
+/// --calendarが指定されていればそのカレンダーのイベントだけにする
+fn load_input(path: &str, config: &Config) -> Result<Input, Error> {
+    // struct Input化した入力ファイル
+    let input = Input::from_file(path)?;
+    return select_calendar(input, config);
+}
 
-        for i in 0..input.events.len() {
-            let csss = &mut MakerCSSs::from_i32(i as i32).unwrap().to_csss();
-            css_vec.append(csss);
-        }
+/// --calendarが指定されていれば、そのカレンダーのイベントだけを持つInputにする
+fn select_calendar(input: Input, config: &Config) -> Result<Input, Error> {
+    return match config.calendar {
+        Some(ref id) => input.calendar(id),
+        None => Ok(input),
+    };
+}
 
-        let mut style = String::new();
-        for css in css_vec {
-            style = format!("{}{}", style, css.to_html());
-        }
+/// 出力ファイルに書き込む
+fn write_file(path: &str, content: &[u8]) -> Result<(), Error> {
+    let io_error = |source| Error::Io { path: path.to_string(), source };
 
-        css.set_text(&style);
+    let file = File::create(path).map_err(io_error)?;
+    let mut buf = BufWriter::new(file);
+    buf.write_all(content).map_err(io_error)?;
+    buf.flush().map_err(io_error)?;
 
-        return css;
-    }
+    return Ok(());
 }
@@ -0,0 +1,595 @@
+use crate::structs::web::css::MakerCSSs;
+use crate::structs::input::Input;
+use crate::schedule::WEEKDAYS;
+use crate::theme::{self, Theme};
+use crate::error::Error;
+
+use chrono::{NaiveDate, Weekday};
+
+/// カレンダーの表示形式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    /// 月ごとのマス目(従来の表示)
+    Grid,
+    /// イベントを日付順に並べた一覧
+    Agenda,
+    /// マス目と一覧の両方
+    Both,
+}
+
+impl Layout {
+    /// コマンドライン引数の文字列からLayoutを得る
+    pub fn from_name(name: &str) -> Option<Layout> {
+        match name {
+            "grid" => Some(Layout::Grid),
+            "agenda" => Some(Layout::Agenda),
+            "both" => Some(Layout::Both),
+            _ => None,
+        }
+    }
+
+    pub fn has_grid(&self) -> bool {
+        return *self != Layout::Agenda;
+    }
+
+    pub fn has_agenda(&self) -> bool {
+        return *self != Layout::Grid;
+    }
+}
+
+/// イベントのある日をクリックしたときに詳細を表示する方法
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Popover {
+    /// Materializeのモーダル
+    Modal,
+    /// <details>要素(JavaScriptを使わない)
+    Details,
+    /// 詳細を表示しない
+    None,
+}
+
+impl Popover {
+    /// コマンドライン引数の文字列からPopoverを得る
+    pub fn from_name(name: &str) -> Option<Popover> {
+        match name {
+            "modal" => Some(Popover::Modal),
+            "details" => Some(Popover::Details),
+            "none" => Some(Popover::None),
+            _ => None,
+        }
+    }
+}
+
+/// カレンダーのマス目に表示する内容
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CellMode {
+    /// 日付とマーカーのみ(従来の表示)
+    Compact,
+    /// 日付の下にイベント名と時刻も表示する
+    Detailed,
+}
+
+impl CellMode {
+    /// コマンドライン引数の文字列からCellModeを得る
+    pub fn from_name(name: &str) -> Option<CellMode> {
+        match name {
+            "compact" => Some(CellMode::Compact),
+            "detailed" => Some(CellMode::Detailed),
+            _ => None,
+        }
+    }
+}
+
+/// カレンダーの各週の先頭に表示する週番号
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekNumbers {
+    /// 週番号を表示しない
+    None,
+    /// ISO 8601の週番号("W14")
+    Iso,
+    /// 入力のsemesterから数えた授業週("第3週")
+    Academic,
+}
+
+impl WeekNumbers {
+    /// コマンドライン引数の文字列からWeekNumbersを得る
+    pub fn from_name(name: &str) -> Option<WeekNumbers> {
+        match name {
+            "none" => Some(WeekNumbers::None),
+            "iso" => Some(WeekNumbers::Iso),
+            "academic" => Some(WeekNumbers::Academic),
+            _ => None,
+        }
+    }
+}
+
+/// 印刷用紙のサイズ
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Paper {
+    A4,
+    A3,
+}
+
+impl Paper {
+    /// コマンドライン引数の文字列からPaperを得る
+    pub fn from_name(name: &str) -> Option<Paper> {
+        match name {
+            "a4" | "A4" => Some(Paper::A4),
+            "a3" | "A3" => Some(Paper::A3),
+            _ => None,
+        }
+    }
+}
+
+/// 印刷(ポスター)向けの出力設定
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrintOptions {
+    /// 用紙サイズ
+    pub paper: Paper,
+    /// 1ページに並べる月の数
+    pub months_per_page: u32,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        return PrintOptions { paper: Paper::A4, months_per_page: 2 };
+    }
+}
+
+/// 週の始まりの曜日
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    Sunday,
+    Monday,
+}
+
+impl WeekStart {
+    /// コマンドライン引数の文字列からWeekStartを得る
+    pub fn from_name(name: &str) -> Option<WeekStart> {
+        match name {
+            "sunday" => Some(WeekStart::Sunday),
+            "monday" => Some(WeekStart::Monday),
+            _ => None,
+        }
+    }
+
+    /// 週の始まりから並べた1週間の曜日
+    pub fn weekdays(&self) -> [Weekday; 7] {
+        let mut weekdays = WEEKDAYS;
+        if *self == WeekStart::Monday {
+            weekdays.rotate_left(1);
+        }
+        return weekdays;
+    }
+
+    /// 週の始まりから数えて何日目の曜日か(0始まり)
+    pub fn offset(&self, weekday: Weekday) -> u32 {
+        match self {
+            WeekStart::Sunday => weekday.num_days_from_sunday(),
+            WeekStart::Monday => weekday.num_days_from_monday(),
+        }
+    }
+}
+
+/// 配色の明暗
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorScheme {
+    /// 端末の設定(prefers-color-scheme)に合わせる
+    Auto,
+    /// 常にテーマの配色
+    Light,
+    /// 常にテーマのダークモードの配色
+    Dark,
+}
+
+impl ColorScheme {
+    /// コマンドライン引数の文字列からColorSchemeを得る
+    pub fn from_name(name: &str) -> Option<ColorScheme> {
+        match name {
+            "auto" => Some(ColorScheme::Auto),
+            "light" => Some(ColorScheme::Light),
+            "dark" => Some(ColorScheme::Dark),
+            _ => None,
+        }
+    }
+}
+
+/// カレンダーの見た目に関する設定
+///
+/// jsonなどの設定ファイルから読み込めるよう、省略された項目はデフォルト値になる
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenderOptions {
+    /// カレンダーの表示形式
+    pub layout: Layout,
+    /// 印刷(ポスター)向けの出力設定. Noneなら印刷モードにしない
+    pub print: Option<PrintOptions>,
+    /// 週の始まりの曜日
+    pub week_start: WeekStart,
+    /// 週番号の表示
+    pub week_numbers: WeekNumbers,
+    /// htmlの言語(lang属性)
+    pub lang: String,
+    /// イベントのある日の詳細の表示方法
+    pub popover: Popover,
+    /// 月の前後の空白に前後の月の日付を表示する
+    pub adjacent_days: bool,
+    /// 連続した2日以上にわたるイベントをマス目をまたぐ帯で表示する
+    pub bars: bool,
+    /// マス目に表示する内容
+    pub cells: CellMode,
+    /// 今日として扱う日付(YYYY-MM-DD). Noneなら閲覧時の日付
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub today: Option<String>,
+    /// Materialize(css)のURL
+    pub materialize_css: String,
+    /// Materialize(js)のURL
+    pub materialize_js: String,
+    /// Material IconsのURL
+    pub material_icons: String,
+    /// テーマの名前
+    pub theme: String,
+    /// 配色の明暗
+    pub color_scheme: ColorScheme,
+    /// navのフォント. Noneならテーマのフォント
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nav_font: Option<String>,
+    /// 強調色(navの背景、凡例の左の線). Noneならテーマの色
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accent_color: Option<String>,
+    /// 凡例の背景色. Noneならテーマの色
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub legend_background: Option<String>,
+    /// イベントのマーカーの色. 空ならテーマの色を使う
+    pub palette: Vec<String>,
+    /// 追加で読み込むcssファイルのpath
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub css_file: Option<String>,
+    /// 追加のcss
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub css: Option<String>,
+    /// htmlのテンプレートファイルのpath. Noneなら組み込みのレイアウトで出力する
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        return RenderOptions {
+            layout: Layout::Grid,
+            print: None,
+            week_start: WeekStart::Sunday,
+            week_numbers: WeekNumbers::None,
+            lang: "ja".to_string(),
+            popover: Popover::Modal,
+            adjacent_days: false,
+            bars: false,
+            cells: CellMode::Compact,
+            today: None,
+            materialize_css: "https://cdnjs.cloudflare.com/ajax/libs/materialize/1.0.0/css/materialize.min.css".to_string(),
+            materialize_js: "https://cdnjs.cloudflare.com/ajax/libs/materialize/1.0.0/js/materialize.min.js".to_string(),
+            material_icons: "https://fonts.googleapis.com/icon?family=Material+Icons".to_string(),
+            theme: theme::LIGHT.name.to_string(),
+            color_scheme: ColorScheme::Auto,
+            nav_font: None,
+            accent_color: None,
+            legend_background: None,
+            palette: Vec::new(),
+            css_file: None,
+            css: None,
+            template: None,
+        };
+    }
+}
+
+impl RenderOptions {
+    /// RenderOptionsBuilderを作る
+    pub fn builder() -> RenderOptionsBuilder {
+        return RenderOptionsBuilder { options: RenderOptions::default() };
+    }
+
+    /// 設定されたテーマ
+    /// color_schemeがDarkならテーマのダークモードの配色
+    /// ダークモードの配色のないテーマ(high-contrastなど)はdarkの配色にする
+    pub fn theme(&self) -> Result<&'static Theme, Error> {
+        let theme = Theme::from_name(&self.theme).ok_or_else(|| Error::UnknownTheme(self.theme.clone()))?;
+        match (self.color_scheme, theme.dark) {
+            (ColorScheme::Dark, Some(dark)) => Ok(dark),
+            (ColorScheme::Dark, None) if theme.name != theme::DARK.name => Ok(&theme::DARK),
+            _ => Ok(theme),
+        }
+    }
+
+    /// 端末がダークモードのときに使う設定
+    /// color_schemeがAutoで、テーマにダークモードの配色があるときのみ
+    pub fn dark_variant(&self) -> Result<Option<RenderOptions>, Error> {
+        if self.color_scheme != ColorScheme::Auto || self.theme()?.dark.is_none() {
+            return Ok(None);
+        }
+
+        let mut dark = self.clone();
+        dark.color_scheme = ColorScheme::Dark;
+        return Ok(Some(dark));
+    }
+
+    /// 今日として扱う日付. Noneなら閲覧時の日付を使う
+    pub fn today(&self) -> Result<Option<NaiveDate>, Error> {
+        match self.today {
+            Some(ref today) => NaiveDate::parse_from_str(today, "%Y-%m-%d")
+                .map(Some)
+                .map_err(|_| Error::InvalidToday(today.clone())),
+            None => Ok(None),
+        }
+    }
+
+    /// navのフォント
+    pub fn nav_font(&self) -> Result<&str, Error> {
+        match self.nav_font {
+            Some(ref font) => Ok(font),
+            None => Ok(self.theme()?.nav_font),
+        }
+    }
+
+    /// navの背景色
+    pub fn nav_background(&self) -> Result<&str, Error> {
+        match self.accent_color {
+            Some(ref color) => Ok(color),
+            None => Ok(self.theme()?.nav_background),
+        }
+    }
+
+    /// 強調色
+    pub fn accent_color(&self) -> Result<&str, Error> {
+        match self.accent_color {
+            Some(ref color) => Ok(color),
+            None => Ok(self.theme()?.accent),
+        }
+    }
+
+    /// 凡例の背景色
+    pub fn legend_background(&self) -> Result<&str, Error> {
+        match self.legend_background {
+            Some(ref color) => Ok(color),
+            None => Ok(self.theme()?.legend_background),
+        }
+    }
+
+    /// マーカーの色の一覧. 空ならMakerCSSsの色を使う
+    fn palette(&self) -> Result<Vec<&str>, Error> {
+        if self.palette.len() > 0 {
+            return Ok(self.palette.iter().map(|color| color.as_str()).collect());
+        }
+        return Ok(self.theme()?.palette.to_vec());
+    }
+
+    /// 区別できるイベントの種類の上限
+    pub fn max_events(&self) -> usize {
+        match self.palette() {
+            Ok(ref palette) if palette.len() > 0 => palette.len(),
+            _ => MakerCSSs::count(),
+        }
+    }
+
+    /// event_index番目のイベントのマーカーの色
+    pub fn marker_color(&self, event_index: i32) -> Result<String, Error> {
+        let palette = self.palette()?;
+        if palette.len() > 0 {
+            return palette.get(event_index as usize).map(|color| color.to_string())
+                .ok_or(Error::TooManyEvents { limit: palette.len(), count: event_index as usize + 1 });
+        }
+        return Ok(MakerCSSs::from_event_index(event_index)?.colorcode().to_string());
+    }
+
+    /// event_index番目のイベントの色
+    /// カテゴリのあるイベントは、カテゴリごとに1つの色の濃淡で塗り分ける
+    pub fn event_color(&self, input: &Input, event_index: i32) -> Result<String, Error> {
+        if input.categories().len() == 0 {
+            return self.marker_color(event_index);
+        }
+
+        for (family, events) in input.color_families().iter().enumerate() {
+            if let Some(shade) = events.iter().position(|i| *i == event_index as usize) {
+                let color = self.marker_color(family as i32)?;
+                return Ok(shade_color(&color, shade));
+            }
+        }
+        return self.marker_color(event_index);
+    }
+
+    /// event_index番目のイベントの印刷時のパターン
+    /// カテゴリのあるイベントは色の系統によらず、イベントごとにパターンを繰り返して使う
+    pub fn event_pattern(&self, input: &Input, event_index: i32) -> Result<MakerCSSs, Error> {
+        if input.categories().len() == 0 {
+            return self.marker_pattern(event_index);
+        }
+        return MakerCSSs::from_event_index(event_index % MakerCSSs::count() as i32);
+    }
+
+    /// event_index番目のイベントのパターン
+    /// paletteで色を増やしたときは、パターンを繰り返して使う
+    pub fn marker_pattern(&self, event_index: i32) -> Result<MakerCSSs, Error> {
+        if self.palette()?.len() > 0 {
+            return MakerCSSs::from_event_index(event_index % MakerCSSs::count() as i32);
+        }
+        return MakerCSSs::from_event_index(event_index);
+    }
+}
+
+/// 1つのカテゴリで使う色の濃淡(混ぜる色, 混ぜる割合)
+/// 白い文字が読めるよう、明るくするのは1段階だけにする
+const SHADES: [(f64, f64); 5] = [(0.0, 0.0), (0.0, 0.2), (255.0, 0.2), (0.0, 0.4), (0.0, 0.6)];
+
+/// 1つのカテゴリに入れられるイベントの数(色の濃淡の数)
+pub const MAX_EVENTS_PER_CATEGORY: usize = SHADES.len();
+
+/// colorの濃淡を変えた色("#rrggbb"の形式でなければそのまま)
+/// shadeが0なら元の色、1以降は順に暗く・明るく・より暗くする
+fn shade_color(color: &str, shade: usize) -> String {
+    let hex = color.trim_start_matches('#');
+    let rgb = match u32::from_str_radix(hex, 16) {
+        Ok(rgb) if shade > 0 && color.starts_with('#') && hex.len() == 6 => rgb,
+        _ => return color.to_string(),
+    };
+
+    let (target, amount) = SHADES[shade % SHADES.len()];
+    let mix = |c: u32| (c as f64 + (target - c as f64) * amount).round() as u32;
+    return format!("#{:02x}{:02x}{:02x}", mix((rgb >> 16) & 0xff), mix((rgb >> 8) & 0xff), mix(rgb & 0xff));
+}
+
+/// RenderOptionsを組み立てる
+///
+/// RenderOptions::builder().layout(Layout::Agenda).week_start(WeekStart::Monday).build()
+///
+#[derive(Debug, Clone)]
+pub struct RenderOptionsBuilder {
+    options: RenderOptions,
+}
+
+impl RenderOptionsBuilder {
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.options.layout = layout;
+        return self;
+    }
+
+    pub fn print(mut self, print: Option<PrintOptions>) -> Self {
+        self.options.print = print;
+        return self;
+    }
+
+    pub fn week_start(mut self, week_start: WeekStart) -> Self {
+        self.options.week_start = week_start;
+        return self;
+    }
+
+    pub fn week_numbers(mut self, week_numbers: WeekNumbers) -> Self {
+        self.options.week_numbers = week_numbers;
+        return self;
+    }
+
+    pub fn lang(mut self, lang: &str) -> Self {
+        self.options.lang = lang.to_string();
+        return self;
+    }
+
+    pub fn popover(mut self, popover: Popover) -> Self {
+        self.options.popover = popover;
+        return self;
+    }
+
+    pub fn adjacent_days(mut self, adjacent_days: bool) -> Self {
+        self.options.adjacent_days = adjacent_days;
+        return self;
+    }
+
+    pub fn bars(mut self, bars: bool) -> Self {
+        self.options.bars = bars;
+        return self;
+    }
+
+    pub fn cells(mut self, cells: CellMode) -> Self {
+        self.options.cells = cells;
+        return self;
+    }
+
+    pub fn today(mut self, today: &str) -> Self {
+        self.options.today = Some(today.to_string());
+        return self;
+    }
+
+    pub fn materialize_css(mut self, url: &str) -> Self {
+        self.options.materialize_css = url.to_string();
+        return self;
+    }
+
+    pub fn materialize_js(mut self, url: &str) -> Self {
+        self.options.materialize_js = url.to_string();
+        return self;
+    }
+
+    pub fn material_icons(mut self, url: &str) -> Self {
+        self.options.material_icons = url.to_string();
+        return self;
+    }
+
+    pub fn theme(mut self, name: &str) -> Self {
+        self.options.theme = name.to_string();
+        return self;
+    }
+
+    pub fn color_scheme(mut self, color_scheme: ColorScheme) -> Self {
+        self.options.color_scheme = color_scheme;
+        return self;
+    }
+
+    pub fn nav_font(mut self, font: &str) -> Self {
+        self.options.nav_font = Some(font.to_string());
+        return self;
+    }
+
+    pub fn accent_color(mut self, color: &str) -> Self {
+        self.options.accent_color = Some(color.to_string());
+        return self;
+    }
+
+    pub fn legend_background(mut self, color: &str) -> Self {
+        self.options.legend_background = Some(color.to_string());
+        return self;
+    }
+
+    pub fn palette(mut self, palette: Vec<String>) -> Self {
+        self.options.palette = palette;
+        return self;
+    }
+
+    pub fn css_file(mut self, path: &str) -> Self {
+        self.options.css_file = Some(path.to_string());
+        return self;
+    }
+
+    pub fn css(mut self, css: &str) -> Self {
+        self.options.css = Some(css.to_string());
+        return self;
+    }
+
+    pub fn template(mut self, path: &str) -> Self {
+        self.options.template = Some(path.to_string());
+        return self;
+    }
+
+    pub fn build(self) -> RenderOptions {
+        return self.options;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dark_scheme_uses_theme_dark_variant() {
+        let options = RenderOptions::builder().theme("light").color_scheme(ColorScheme::Dark).build();
+        assert_eq!(options.theme().unwrap().name, "dark");
+    }
+
+    #[test]
+    fn dark_scheme_without_dark_variant_falls_back_to_dark() {
+        let options = RenderOptions::builder().theme("high-contrast").color_scheme(ColorScheme::Dark).build();
+        assert_eq!(options.theme().unwrap().name, "dark");
+        let options = RenderOptions::builder().theme("dark").color_scheme(ColorScheme::Dark).build();
+        assert_eq!(options.theme().unwrap().name, "dark");
+    }
+
+    #[test]
+    fn light_scheme_keeps_theme() {
+        let options = RenderOptions::builder().theme("high-contrast").color_scheme(ColorScheme::Light).build();
+        assert_eq!(options.theme().unwrap().name, "high-contrast");
+    }
+}
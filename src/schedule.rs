@@ -0,0 +1,538 @@
+use crate::structs::input::Input;
+use crate::structs::input::event::Status;
+use crate::structs::date::MonthNames;
+use crate::options::{WeekStart, WeekNumbers};
+use crate::error::Error;
+
+use chrono::{NaiveDate, Weekday, Datelike, Duration};
+
+/// 日曜日から始まる1週間の曜日
+pub const WEEKDAYS: [Weekday; 7] = [Weekday::Sun, Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat];
+
+/// コンピュータ上にカレンダーを再現する
+/// (日時, その日に開催されるイベントのindex一覧)の列を返す
+pub fn calc_calendar(input: &Input) -> Result<Vec<(NaiveDate, Vec<i32>)>, Error> {
+    // 表示する範囲の指定があればそれに従う
+    let start = match input.start {
+        Some(ref bound) => Some(bound.first_day(input.year)?),
+        None => None,
+    };
+    let end = match input.end {
+        Some(ref bound) => Some(bound.last_day(input.year)?),
+        None => None,
+    };
+    if let (Some(start), Some(end)) = (start, end) {
+        if start > end {
+            return Err(Error::InvalidRange { start, end });
+        }
+    }
+
+    // 何月から何月までのcalendarを作成する必要があるのかを探る
+    // イベントが範囲の外にあればエラー
+    let shown = input.shown_events();
+    let mut first_event: Option<NaiveDate> = None;
+    let mut last_event: Option<NaiveDate> = None;
+    for i in &shown {
+        let event = &input.events[*i];
+        for date in event_dates(input, *i)? {
+            if let Some(start) = start.filter(|start| date < *start) {
+                return Err(Error::BeforeStart { event: event.name.clone(), date, start });
+            }
+            if let Some(end) = end.filter(|end| date > *end) {
+                return Err(Error::AfterEnd { event: event.name.clone(), date, end });
+            }
+            first_event = Some(first_event.map_or(date, |first| first.min(date)));
+            last_event = Some(last_event.map_or(date, |last| last.max(date)));
+        }
+    }
+
+    // 範囲の片側だけが指定され、イベントもなければ、指定された月だけを表示する
+    let (min_month, max_month) = match (start.or(first_event).or(end), end.or(last_event).or(start)) {
+        (Some(first), Some(last)) => (first.month(), last.month()),
+        _ => return Ok(Vec::new()),
+    };
+
+    // 必要な月を出力
+    let first_day = NaiveDate::from_ymd_opt(input.year, min_month, 1)
+        .ok_or(Error::InvalidDate { year: input.year, month: min_month, day: None })?;
+    let the_day_after_last_day = first_day_of_next_month(input.year, max_month)?;
+    // (日時,イベントid)
+    let mut schedules: Vec<(NaiveDate, Vec<i32>)> = Vec::new();
+
+    // カレンダーに出力されるdayをvecにしまっておく
+    for day in first_day.iter_days().take_while(|day| *day < the_day_after_last_day) {
+        schedules.push((day, Vec::new()));
+    }
+
+    // イベントとcalendar_vec内のNativeDateを紐付ける
+    // iはイベントindex
+    for i in shown {
+        // イベント開催日の配列
+        let event_dates = event_dates(input, i)?;
+
+        // 総当たりでイベント開催日とカレンダーをマッチング
+        for schedule in &mut schedules {
+            let (day, ref mut events) = *schedule;
+            // match
+            if event_dates.contains(&day) {
+                events.push(i as i32);
+            }
+        }
+    }
+
+    return Ok(schedules);
+}
+
+/// event_index番目のイベントの開催日を日付順に並べる
+pub fn event_dates(input: &Input, event_index: usize) -> Result<Vec<NaiveDate>, Error> {
+    let event = &input.events[event_index];
+
+    let mut event_dates: Vec<NaiveDate> = Vec::new();
+    for date in &event.dates {
+        // 存在しない月は日を見る前に、書かれた月のままエラーにする
+        if !(1..=12).contains(&date.month) {
+            return Err(Error::InvalidDate { year: input.year, month: date.month, day: None });
+        }
+        for day in &date.days {
+            let event_date = NaiveDate::from_ymd_opt(input.year, date.month, *day)
+                .ok_or(Error::InvalidDate { year: input.year, month: date.month, day: Some(*day) })?;
+            event_dates.push(event_date);
+        }
+    }
+    event_dates.sort();
+
+    return Ok(event_dates);
+}
+
+/// scheduleを月ごとに分ける
+pub fn split_monthly(schedule: Vec<(NaiveDate, Vec<i32>)>) -> Vec<Vec<(NaiveDate, Vec<i32>)>> {
+    let mut schedules_monthly: Vec<Vec<(NaiveDate, Vec<i32>)>> = Vec::new();
+    let mut m = 0;
+    let mut index = 0;
+    for sch in schedule {
+        let (day, events) = sch;
+        if m == 0 {
+            m = day.month();
+            schedules_monthly.push(Vec::new());
+        } else if m != day.month() {
+            index += 1;
+            m = day.month();
+            schedules_monthly.push(Vec::new());
+        }
+        schedules_monthly[index].push((day, events));
+    }
+
+    return schedules_monthly;
+}
+
+/// 1週間分のschedule(月の前後の空白はNone)
+pub type Week<'a> = Vec<Option<&'a (NaiveDate, Vec<i32>)>>;
+
+/// 1ヶ月分のscheduleをweek_startから始まる週ごとに分ける
+pub fn split_weekly(schedule_monthly: &[(NaiveDate, Vec<i32>)], week_start: WeekStart) -> Vec<Week<'_>> {
+    let mut weeks = Vec::new();
+    let mut week: Week = Vec::new();
+
+    let (ref first_day, _) = schedule_monthly[0];
+    for _ in 0..week_start.offset(first_day.weekday()) {
+        week.push(None);
+    }
+    for schedule in schedule_monthly {
+        week.push(Some(schedule));
+        if week.len() == 7 {
+            weeks.push(week);
+            week = Vec::new();
+        }
+    }
+    if week.len() > 0 {
+        while week.len() < 7 {
+            week.push(None);
+        }
+        weeks.push(week);
+    }
+
+    return weeks;
+}
+
+/// first_dayから始まる週(カレンダーの1行)の週番号の表示
+/// 表示しないとき、授業週の数え方で学期の外や休みの週はNone
+pub fn week_label(input: &Input, first_day: NaiveDate, week_numbers: WeekNumbers, week_start: WeekStart) -> Result<Option<String>, Error> {
+    match week_numbers {
+        WeekNumbers::None => Ok(None),
+        WeekNumbers::Iso => {
+            // ISO 8601の週は月曜から始まる
+            let monday = first_day + Duration::days(week_start.offset(Weekday::Mon) as i64);
+            Ok(Some(format!("W{}", monday.iso_week().week())))
+        }
+        WeekNumbers::Academic => {
+            let semester = input.semester.as_ref().ok_or(Error::MissingSemester)?;
+            Ok(semester.week_number(first_day, week_start)?.map(|number| format!("第{}週", number)))
+        }
+    }
+}
+
+/// monthの翌月の1日を返す(12月の翌月は翌年の1月)
+fn first_day_of_next_month(year: i32, month: u32) -> Result<NaiveDate, Error> {
+    let (year, month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    return NaiveDate::from_ymd_opt(year, month, 1).ok_or(Error::InvalidDate { year, month, day: None });
+}
+
+/// dateが属する月の名前
+pub fn month_name(date: &NaiveDate) -> Result<MonthNames, Error> {
+    return MonthNames::from_u32(date.month0()).ok_or(Error::InvalidDate { year: date.year(), month: date.month(), day: None });
+}
+
+/// scheduleからdateに開催されるイベントのindex一覧を得る
+/// scheduleに含まれない日付なら空
+pub fn events_on(schedule: &[(NaiveDate, Vec<i32>)], date: NaiveDate) -> &[i32] {
+    match schedule.binary_search_by_key(&date, |(day, _)| *day) {
+        Ok(index) => &schedule[index].1,
+        Err(_) => &[],
+    }
+}
+
+/// 連続した2日以上にわたるイベントの帯の、1日分の部分
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BarPiece {
+    pub event_index: i32,
+    /// 週(カレンダーの1行)の中で帯が始まる日か
+    pub start: bool,
+    /// 週(カレンダーの1行)の中で帯が終わる日か
+    pub end: bool,
+}
+
+/// 1週間分(カレンダーの1行)の日付について、帯で表示するイベントを段に分けて並べる
+/// daysは行の左から並べた日付(表示しないマス目はNone)
+/// 返り値はマス目ごとの段の一覧で、段の数は行の中で共通(帯のない段はNone)
+pub fn week_bars(schedule: &[(NaiveDate, Vec<i32>)], days: &[Option<NaiveDate>]) -> Vec<Vec<Option<BarPiece>>> {
+    let on = |event_index: i32, day: NaiveDate| events_on(schedule, day).contains(&event_index);
+    // 前後の日にも開催されるイベントは帯にする
+    let is_bar = |event_index: i32, day: NaiveDate| {
+        on(event_index, day) && (on(event_index, day - Duration::days(1)) || on(event_index, day + Duration::days(1)))
+    };
+
+    // 行の中で帯になるイベントを、イベントのindex順に1段ずつ割り当てる
+    let mut lanes: Vec<i32> = Vec::new();
+    for day in days.iter().flatten() {
+        for event_index in events_on(schedule, *day) {
+            if is_bar(*event_index, *day) && !lanes.contains(event_index) {
+                lanes.push(*event_index);
+            }
+        }
+    }
+    lanes.sort();
+
+    let mut bars = Vec::new();
+    for (column, day) in days.iter().enumerate() {
+        let day = match day {
+            Some(day) => *day,
+            None => {
+                bars.push(Vec::new());
+                continue;
+            }
+        };
+        // 隣のマス目に同じイベントが表示されていれば帯をつなげる
+        let continues = |column: Option<usize>, event_index: i32| {
+            match column.and_then(|column| days.get(column)) {
+                Some(Some(neighbor)) => on(event_index, *neighbor),
+                _ => false,
+            }
+        };
+        let pieces = lanes.iter().map(|event_index| {
+            if !is_bar(*event_index, day) {
+                return None;
+            }
+            return Some(BarPiece {
+                event_index: *event_index,
+                start: !continues(column.checked_sub(1), *event_index),
+                end: !continues(Some(column + 1), *event_index),
+            });
+        }).collect();
+        bars.push(pieces);
+    }
+
+    return bars;
+}
+
+/// マーカーで示すイベント(帯で表示するイベントを除く)
+pub fn marker_events(events: &[i32], bars: &[Option<BarPiece>]) -> Vec<i32> {
+    return events.iter()
+        .filter(|event_index| !bars.iter().flatten().any(|piece| piece.event_index == **event_index))
+        .copied()
+        .collect();
+}
+
+/// event_index番目のイベントを示すアルファベット(A, B, ..., Z, AA, AB, ...)
+pub fn event_letter(event_index: i32) -> String {
+    let mut letters = Vec::new();
+    let mut n = event_index.max(0) as u32 + 1;
+    while n > 0 {
+        n -= 1;
+        letters.push(char::from_u32('A' as u32 + n % 26).unwrap_or('?'));
+        n /= 26;
+    }
+    return letters.iter().rev().collect();
+}
+
+/// event_index番目のイベントのdayの状態(開催日でなければ確定)
+pub fn event_status(input: &Input, event_index: i32, day: &NaiveDate) -> Status {
+    if day.year() != input.year {
+        return Status::Confirmed;
+    }
+    let event = match input.events.get(event_index as usize) {
+        Some(event) => event,
+        None => return Status::Confirmed,
+    };
+    return event.dates.iter()
+        .find(|date| date.month == day.month() && date.days.contains(&day.day()))
+        .map(|date| date.status)
+        .unwrap_or_default();
+}
+
+/// イベントのすべての開催日に共通する状態
+/// 開催日によって状態が違えば確定として扱う
+pub fn event_overall_status(input: &Input, event_index: usize) -> Status {
+    let event = &input.events[event_index];
+    match event.dates.first() {
+        Some(first) if event.dates.iter().all(|date| date.status == first.status) => first.status,
+        _ => Status::Confirmed,
+    }
+}
+
+/// イベントの開催日のうち、確定していないものの説明("4/24 中止"など)
+/// すべての開催日が同じ状態ならその説明("中止"など)のみ
+pub fn event_status_notes(input: &Input, event_index: usize) -> Vec<(Status, String)> {
+    let overall = event_overall_status(input, event_index);
+    if let Some(label) = overall.label() {
+        return vec![(overall, label.to_string())];
+    }
+
+    let mut notes = Vec::new();
+    for date in &input.events[event_index].dates {
+        if let Some(label) = date.status.label() {
+            let days: Vec<String> = date.days.iter().map(|day| format!("{}/{}", date.month, day)).collect();
+            notes.push((date.status, format!("{} {}", days.join("・"), label)));
+        }
+    }
+    return notes;
+}
+
+/// マス目に表示するイベントの説明の全文("18:30-20:00 ミーティング(中止)")
+pub fn event_label_title(input: &Input, event_index: i32, day: &NaiveDate) -> String {
+    let event = &input.events[event_index as usize];
+    let mut title = match event.time {
+        Some(ref time) => format!("{} {}", time, event.name),
+        None => event.name.clone(),
+    };
+    if let Some(label) = event_status(input, event_index, day).label() {
+        title = format!("{}({})", title, label);
+    }
+    return title;
+}
+
+/// 曜日の日本語の名前("水曜日"など)
+pub fn weekday_name_ja(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Sun => "日曜日",
+        Weekday::Mon => "月曜日",
+        Weekday::Tue => "火曜日",
+        Weekday::Wed => "水曜日",
+        Weekday::Thu => "木曜日",
+        Weekday::Fri => "金曜日",
+        Weekday::Sat => "土曜日",
+    }
+}
+
+/// "4月10日 水曜日"のような日付の見出し
+pub fn day_title(day: &NaiveDate) -> String {
+    return format!("{}月{}日 {}", day.month(), day.day(), weekday_name_ja(day.weekday()));
+}
+
+/// 読み上げ用の日付とイベントの説明("4月10日 水曜日: ミーティング")
+pub fn day_label(input: &Input, day: &NaiveDate, events: &[i32]) -> String {
+    let date = day_title(day);
+    if events.len() == 0 {
+        return date;
+    }
+
+    let names: Vec<String> = events.iter()
+        .filter_map(|event_index| input.events.get(*event_index as usize).map(|event| (event_index, event)))
+        .map(|(event_index, event)| match event_status(input, *event_index, day).label() {
+            Some(label) => format!("{}({})", event.name, label),
+            None => event.name.clone(),
+        })
+        .collect();
+    return format!("{}: {}", date, names.join("、"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(json: &str) -> Input {
+        return Input::from_json(json).unwrap();
+    }
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        return NaiveDate::from_ymd_opt(2019, month, day).unwrap();
+    }
+
+    #[test]
+    fn start_date_is_checked_by_day() {
+        let input = input(r#"{ "year": 2019, "title": "t", "start": "2019-04-15", "events": [{ "name": "x", "dates": [{ "month": 4, "days": [10] }] }] }"#);
+        match calc_calendar(&input) {
+            Err(Error::BeforeStart { date: event_date, start, .. }) => {
+                assert_eq!(event_date, date(4, 10));
+                assert_eq!(start, date(4, 15));
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn end_date_is_checked_by_day() {
+        let input = input(r#"{ "year": 2019, "title": "t", "end": "2019-04-05", "events": [{ "name": "x", "dates": [{ "month": 4, "days": [10] }] }] }"#);
+        assert!(matches!(calc_calendar(&input), Err(Error::AfterEnd { .. })));
+    }
+
+    #[test]
+    fn month_bound_covers_whole_month() {
+        let input = input(r#"{ "year": 2019, "title": "t", "start": 4, "end": "2019-04", "events": [{ "name": "x", "dates": [{ "month": 4, "days": [1, 30] }] }] }"#);
+        let schedule = calc_calendar(&input).unwrap();
+        assert_eq!(schedule.first().unwrap().0, date(4, 1));
+        assert_eq!(schedule.last().unwrap().0, date(4, 30));
+    }
+
+    #[test]
+    fn leading_empty_month() {
+        let input = input(r#"{ "year": 2019, "title": "t", "start": 3, "events": [{ "name": "x", "dates": [{ "month": 4, "days": [10] }] }] }"#);
+        let schedule = calc_calendar(&input).unwrap();
+        assert_eq!(schedule.first().unwrap().0, date(3, 1));
+        assert_eq!(schedule.last().unwrap().0, date(4, 30));
+    }
+
+    #[test]
+    fn start_without_events() {
+        let input = input(r#"{ "year": 2019, "title": "t", "start": 4, "events": [] }"#);
+        let schedule = calc_calendar(&input).unwrap();
+        assert_eq!(schedule.first().unwrap().0, date(4, 1));
+        assert_eq!(schedule.last().unwrap().0, date(4, 30));
+    }
+
+    #[test]
+    fn invalid_month_is_reported_as_written() {
+        let input = input(r#"{ "year": 2019, "title": "t", "events": [{ "name": "x", "dates": [{ "month": 13, "days": [1] }] }] }"#);
+        match calc_calendar(&input) {
+            Err(Error::InvalidDate { year, month, day }) => assert_eq!((year, month, day), (2019, 13, None)),
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn event_letters_continue_after_z() {
+        assert_eq!(event_letter(0), "A");
+        assert_eq!(event_letter(25), "Z");
+        assert_eq!(event_letter(26), "AA");
+        assert_eq!(event_letter(701), "ZZ");
+        assert_eq!(event_letter(702), "AAA");
+    }
+
+    #[test]
+    fn start_after_end() {
+        let input = input(r#"{ "year": 2019, "title": "t", "start": "2019-04-20", "end": "2019-04-10", "events": [] }"#);
+        assert!(matches!(calc_calendar(&input), Err(Error::InvalidRange { .. })));
+    }
+
+    /// 4/1から5/31までのschedule
+    /// 0: 4/5(金)から4/9(火)、1: 4/8と4/9、2: 4/9だけ、3: 4/29から5/2
+    fn bar_schedule() -> Vec<(NaiveDate, Vec<i32>)> {
+        let first_day = date(4, 1);
+        return first_day.iter_days().take_while(|day| *day <= date(5, 31)).map(|day| {
+            let mut events = Vec::new();
+            if date(4, 5) <= day && day <= date(4, 9) {
+                events.push(0);
+            }
+            if date(4, 8) <= day && day <= date(4, 9) {
+                events.push(1);
+            }
+            if day == date(4, 9) {
+                events.push(2);
+            }
+            if date(4, 29) <= day && day <= date(5, 2) {
+                events.push(3);
+            }
+            return (day, events);
+        }).collect();
+    }
+
+    /// first_dayから始まる1週間の日付
+    fn week(first_day: NaiveDate) -> Vec<Option<NaiveDate>> {
+        return first_day.iter_days().take(7).map(Some).collect();
+    }
+
+    fn piece(event_index: i32, start: bool, end: bool) -> Option<BarPiece> {
+        return Some(BarPiece { event_index, start, end });
+    }
+
+    #[test]
+    fn bar_is_split_at_week_edge() {
+        let schedule = bar_schedule();
+        let bars = week_bars(&schedule, &week(date(3, 31)));
+        assert_eq!(bars[4], vec![None]);
+        assert_eq!(bars[5], vec![piece(0, true, false)]);
+        assert_eq!(bars[6], vec![piece(0, false, true)]);
+
+        let bars = week_bars(&schedule, &week(date(4, 7)));
+        assert_eq!(bars[0], vec![piece(0, true, false), None]);
+        assert_eq!(bars[1], vec![piece(0, false, false), piece(1, true, false)]);
+        assert_eq!(bars[2], vec![piece(0, false, true), piece(1, false, true)]);
+        assert_eq!(bars[3], vec![None, None]);
+    }
+
+    #[test]
+    fn lanes_follow_event_index_and_skip_single_days() {
+        let schedule = bar_schedule();
+        let bars = week_bars(&schedule, &week(date(4, 7)));
+        assert!(bars.iter().all(|lanes| lanes.len() == 2));
+        assert!(bars.iter().flatten().flatten().all(|piece| piece.event_index != 2));
+        assert_eq!(marker_events(events_on(&schedule, date(4, 9)), &bars[2]), vec![2]);
+
+        let bars = week_bars(&schedule, &week(date(4, 14)));
+        assert!(bars.iter().all(|lanes| lanes.is_empty()));
+    }
+
+    #[test]
+    fn bar_ends_at_month_edge_without_adjacent_days() {
+        let schedule = bar_schedule();
+        let mut days = week(date(4, 28));
+        for day in days.iter_mut().skip(3) {
+            *day = None;
+        }
+        let bars = week_bars(&schedule, &days);
+        assert_eq!(bars[0], vec![None]);
+        assert_eq!(bars[1], vec![piece(3, true, false)]);
+        assert_eq!(bars[2], vec![piece(3, false, true)]);
+        assert!(bars[3].is_empty());
+
+        // 5月の最初の行では5/1から帯が始まる
+        let mut days = week(date(4, 28));
+        for day in days.iter_mut().take(3) {
+            *day = None;
+        }
+        let bars = week_bars(&schedule, &days);
+        assert!(bars[2].is_empty());
+        assert_eq!(bars[3], vec![piece(3, true, false)]);
+        assert_eq!(bars[4], vec![piece(3, false, true)]);
+    }
+
+    #[test]
+    fn bar_continues_into_adjacent_days() {
+        let schedule = bar_schedule();
+        let bars = week_bars(&schedule, &week(date(4, 28)));
+        assert_eq!(bars[1], vec![piece(3, true, false)]);
+        assert_eq!(bars[2], vec![piece(3, false, false)]);
+        assert_eq!(bars[3], vec![piece(3, false, false)]);
+        assert_eq!(bars[4], vec![piece(3, false, true)]);
+        assert_eq!(bars[5], vec![None]);
+    }
+}
@@ -16,6 +16,8 @@ pub mod web {
             class_list: Vec<String>,
             children: Vec<Element>,
             text: String,
+            /// textをエスケープせずに出力するか(script, style)
+            raw: bool,
             /// attribute (key,value)
             attributes: Vec<(String, String)>,
         }
@@ -24,7 +26,7 @@ pub mod web {
             /// tag名からElementを作る
             /// js -> Document.createElement(tag)
             pub fn create(tag: &str) -> Self {
-                return Self { tag: tag.to_string(), id: String::new(), class_list: Vec::new(), children: Vec::new(), text: String::new(), attributes: Vec::new() };
+                return Self { tag: tag.to_string(), id: String::new(), class_list: Vec::new(), children: Vec::new(), text: String::new(), raw: false, attributes: Vec::new() };
             }
 
             /// Elementの子要素の最後に追加する
@@ -49,14 +51,37 @@ pub mod web {
             }
 
             /// Elementにattributeを与える
+            /// js -> Element.setAttribute(key, val)
+            /// すでに同じkeyのattributeがあれば値を置き換える
             pub fn set_attribute(&mut self, key: &str, val: &str) {
+                if let Some(attribute) = self.attributes.iter_mut().find(|(k, _)| k == key) {
+                    attribute.1 = val.to_string();
+                    return;
+                }
                 let attribute = (key.to_string(), val.to_string());
                 self.attributes.push(attribute);
             }
 
             /// ElementにinnerTextを設定する
+            /// 出力するときに & < > " ' はエスケープされる
             pub fn set_text(&mut self, text: &String) {
                 self.text = text.to_string();
+                self.raw = false;
+            }
+
+            /// Elementにエスケープしない中身を設定する
+            /// scriptやstyleの中身に使う
+            pub fn set_html(&mut self, html: &String) {
+                self.text = html.to_string();
+                self.raw = true;
+            }
+
+            /// 出力するtext
+            fn text_html(&self) -> String {
+                if self.raw {
+                    return self.text.clone();
+                }
+                return escape(&self.text);
             }
         }
 
@@ -72,22 +97,22 @@ pub mod web {
                 return format!("<{}{}{}{}>{}{}</{}>",
                                &self.tag,
                                // idがあれば出力
-                               if self.id.len() == 0 { String::new() } else { format!(" id=\"{}\"", self.id) },
+                               if self.id.len() == 0 { String::new() } else { format!(" id=\"{}\"", escape(&self.id)) },
                                // classがあれば出力
-                               if self.class_list.len() == 0 { String::new() } else { format!(" class=\"{}\"", &self.class_list.join(" ")) },
+                               if self.class_list.len() == 0 { String::new() } else { format!(" class=\"{}\"", escape(&self.class_list.join(" "))) },
                                // attributeがあれば出力
                                if self.attributes.len() == 0 { String::new() } else { attributes_to_html(&self.attributes) },
                                // textがあれば出力
-                               if self.text.len() == 0 { String::new() } else { format!("\n    {}", self.text.clone()) },
+                               if self.text.len() == 0 { String::new() } else { format!("\n    {}", self.text_html()) },
                                // 子要素があれば出力
-                               if self.children.len() == 0 { String::new() } else { format!("{}", children_html) },
+                               if self.children.len() == 0 { String::new() } else { children_html },
                                &self.tag);
             }
 
             fn to_html_index_noted(&self, i: i32) -> String {
                 // インデント(1tab=4space)
                 let mut indent = String::new();
-                for j in 0..i {
+                for _ in 0..i {
                     indent = format!("{}{}", indent, "    ");
                 }
 
@@ -98,13 +123,13 @@ pub mod web {
                                    &indent,
                                    &self.tag,
                                    // idがあれば出力
-                                   if self.id.len() == 0 { String::new() } else { format!(" id=\"{}\"", self.id) },
+                                   if self.id.len() == 0 { String::new() } else { format!(" id=\"{}\"", escape(&self.id)) },
                                    // classがあれば出力
-                                   if self.class_list.len() == 0 { String::new() } else { format!(" class=\"{}\"", &self.class_list.join(" ")) },
+                                   if self.class_list.len() == 0 { String::new() } else { format!(" class=\"{}\"", escape(&self.class_list.join(" "))) },
                                    // attributeがあれば出力
                                    if self.attributes.len() == 0 { String::new() } else { attributes_to_html(&self.attributes) },
                                    // textがあれば出力
-                                   if self.text.len() == 0 { String::new() } else { format!("{}", self.text.clone()) },
+                                   if self.text.len() == 0 { String::new() } else { self.text_html() },
                                    &self.tag
                     );
                 }
@@ -122,13 +147,13 @@ pub mod web {
                                &indent,
                                &self.tag,
                                // idがあれば出力
-                               if self.id.len() == 0 { String::new() } else { format!(" id=\"{}\"", self.id) },
+                               if self.id.len() == 0 { String::new() } else { format!(" id=\"{}\"", escape(&self.id)) },
                                // classがあれば出力
-                               if self.class_list.len() == 0 { String::new() } else { format!(" class=\"{}\"", &self.class_list.join(" ")) },
+                               if self.class_list.len() == 0 { String::new() } else { format!(" class=\"{}\"", escape(&self.class_list.join(" "))) },
                                // attributeがあれば出力
                                if self.attributes.len() == 0 { String::new() } else { attributes_to_html(&self.attributes) },
                                // textがあれば出力
-                               if self.text.len() == 0 { String::new() } else { format!("\n    {}", self.text.clone()) },
+                               if self.text.len() == 0 { String::new() } else { format!("\n    {}", self.text_html()) },
                                // 子要素があれば出力
                                &children_html,
                                &indent,
@@ -137,20 +162,39 @@ pub mod web {
         }
 
         // attribute: Vec<(String, String)>をhtmlに用いられる形に変換
-        fn attributes_to_html(attributes: &Vec<(String, String)>) -> String {
+        fn attributes_to_html(attributes: &[(String, String)]) -> String {
             let mut attributes_html = String::new();
             for attribute in attributes {
                 let (key, val) = attribute;
-                attributes_html = format!("{} {}=\"{}\"", attributes_html, key, val);
+                attributes_html = format!("{} {}=\"{}\"", attributes_html, key, escape(val));
             }
 
             return attributes_html;
         }
+
+        /// html・svgのtextやattributeの値に使えるよう & < > " ' をエスケープする
+        /// テンプレート(minijinja)の自動エスケープと同じ文字参照を使う
+        pub fn escape(text: &str) -> String {
+            let mut escaped = String::with_capacity(text.len());
+            for c in text.chars() {
+                match c {
+                    '&' => escaped.push_str("&amp;"),
+                    '<' => escaped.push_str("&lt;"),
+                    '>' => escaped.push_str("&gt;"),
+                    '"' => escaped.push_str("&quot;"),
+                    '\'' => escaped.push_str("&#x27;"),
+                    c => escaped.push(c),
+                }
+            }
+            return escaped;
+        }
     }
 
     pub mod css {
         use super::ToHtml;
+        use crate::error::Error;
         use num_derive::FromPrimitive;
+        use num_traits::FromPrimitive;
 
         /// cssのセレクター(複数可)と宣言ブロックのセット
         ///
@@ -170,7 +214,7 @@ pub mod web {
 
         impl ToHtml for CSS {
             fn to_html(&self) -> String {
-                let mut selectors = self.selectors.join(" ");
+                let selectors = self.selectors.join(" ");
                 let mut declarations = String::new();
                 for declaration in &self.declarations {
                     let (key, val) = declaration;
@@ -180,7 +224,7 @@ pub mod web {
                 return format!("{}{}{}{}", selectors, " {\n", declarations, "}\n");
             }
 
-            fn to_html_index_noted(&self, i: i32) -> String {
+            fn to_html_index_noted(&self, _i: i32) -> String {
                 return self.to_html();
             }
         }
@@ -202,8 +246,49 @@ pub mod web {
             }
         }
 
+        /// メディアクエリ(@media)で条件付けられたcssの集合
+        ///
+        /// @media print {
+        ///     elem.class { ... }
+        /// }
+        ///
+        /// -> Media { query: "print", csss: [CSS { selector: [elem.class], ... }] }
+        ///
+        #[derive(Debug)]
+        pub struct Media {
+            /// メディアクエリ
+            query: String,
+            /// 条件が満たされたときに適用するcss
+            csss: Vec<CSS>,
+        }
+
+        impl ToHtml for Media {
+            fn to_html(&self) -> String {
+                let mut csss = String::new();
+                for css in &self.csss {
+                    csss = format!("{}{}", csss, css.to_html());
+                }
+
+                return format!("@media {} {{\n{}}}\n", self.query, csss);
+            }
+
+            fn to_html_index_noted(&self, _i: i32) -> String {
+                return self.to_html();
+            }
+        }
+
+        impl Media {
+            pub fn create(query: &str) -> Media {
+                return Media { query: query.to_string(), csss: Vec::new() };
+            }
+
+            pub fn push_css(&mut self, css: CSS) {
+                self.csss.push(css);
+            }
+        }
+
         /// markerに適用するcssのパターン列挙
-        #[derive(FromPrimitive)]
+        #[derive(Debug, FromPrimitive)]
         pub enum MakerCSSs {
             Pattern0 = 0,
             Pattern1,
@@ -213,31 +298,110 @@ pub mod web {
         }
 
         impl MakerCSSs {
+            /// パターンの数(区別できるイベントの種類の上限)
+            pub fn count() -> usize {
+                let mut count = 0;
+
+                loop {
+                    match MakerCSSs::from_i32(count) {
+                        None => break,
+                        _ => { count += 1; }
+                    }
+                }
+
+                return count as usize;
+            }
+
+            /// event_index番目のイベントに用いるパターン
+            pub fn from_event_index(event_index: i32) -> Result<MakerCSSs, Error> {
+                return MakerCSSs::from_i32(event_index).ok_or(Error::TooManyEvents { limit: MakerCSSs::count(), count: event_index as usize + 1 });
+            }
+
             pub fn to_csss(&self) -> Vec<CSS> {
+                return MakerCSSs::csss_from_colorcode(self.index(), self.colorcode(), true);
+            }
+
+            /// パターンの番号(event_index)
+            pub fn index(&self) -> u32 {
+                match self {
+                    MakerCSSs::Pattern0 => 0,
+                    MakerCSSs::Pattern1 => 1,
+                    MakerCSSs::Pattern2 => 2,
+                    MakerCSSs::Pattern3 => 3,
+                    MakerCSSs::Pattern4 => 4,
+                }
+            }
+
+            /// マーカーの色
+            pub fn colorcode(&self) -> &'static str {
+                match self {
+                    // blue
+                    MakerCSSs::Pattern0 => "#2196F3",
+                    // red
+                    MakerCSSs::Pattern1 => "#F44336",
+                    // teal
+                    MakerCSSs::Pattern2 => "#009688",
+                    // deep purple
+                    MakerCSSs::Pattern3 => "#5e35b1",
+                    // orange
+                    MakerCSSs::Pattern4 => "#fb8c00",
+                }
+            }
+
+            /// テキスト出力でマーカーの代わりに用いる絵文字(色をマーカーに合わせる)
+            pub fn emoji(&self) -> &'static str {
+                match self {
+                    MakerCSSs::Pattern0 => "🟦",
+                    MakerCSSs::Pattern1 => "🟥",
+                    MakerCSSs::Pattern2 => "🟩",
+                    MakerCSSs::Pattern3 => "🟪",
+                    MakerCSSs::Pattern4 => "🟧",
+                }
+            }
+
+            /// 印刷時に適用するcss
+            /// グレースケールで印刷しても見分けられるよう、パターンごとに枠線と網掛けを変える
+            pub fn to_print_csss(&self) -> Vec<CSS> {
+                return self.to_print_csss_for(self.index());
+            }
+
+            /// event_index番目のイベントにこのパターンを適用する、印刷時のcss
+            pub fn to_print_csss_for(&self, event_index: u32) -> Vec<CSS> {
                 match self {
                     MakerCSSs::Pattern0 => {
-                        // blue
-                        return MakerCSSs::csss_from_colorcode(0, "#2196F3", true);
+                        // 実線, 網掛けなし
+                        return MakerCSSs::print_csss_from_pattern(event_index, "solid", None);
                     }
                     MakerCSSs::Pattern1 => {
-                        // red
-                        return MakerCSSs::csss_from_colorcode(1, "#F44336", true);
+                        // 破線, 右上がりの斜線
+                        return MakerCSSs::print_csss_from_pattern(event_index, "dashed", Some(45));
                     }
                     MakerCSSs::Pattern2 => {
-                        // teal
-                        return MakerCSSs::csss_from_colorcode(2, "#009688", true);
+                        // 点線, 横縞
+                        return MakerCSSs::print_csss_from_pattern(event_index, "dotted", Some(0));
                     }
                     MakerCSSs::Pattern3 => {
-                        // deep purple
-                        return MakerCSSs::csss_from_colorcode(3, "#5e35b1", true);
+                        // 二重線, 右下がりの斜線
+                        return MakerCSSs::print_csss_from_pattern(event_index, "double", Some(135));
                     }
                     MakerCSSs::Pattern4 => {
-                        // orange
-                        return MakerCSSs::csss_from_colorcode(4, "#fb8c00", true);
+                        // 実線, 縦縞
+                        return MakerCSSs::print_csss_from_pattern(event_index, "solid", Some(90));
                     }
                 }
             }
 
+            /// hatch_angle: 網掛けの角度(deg). Noneなら網掛けしない
+            pub fn print_csss_from_pattern(index: u32, border_style: &str, hatch_angle: Option<u32>) -> Vec<CSS> {
+                let mut css = CSS::create(&format!("[event_index=\"{}\"]", index));
+                css.push_declaration("border", &format!("3px {} black", border_style));
+                if let Some(angle) = hatch_angle {
+                    css.push_declaration("background-image", &format!("repeating-linear-gradient({}deg, rgba(255,255,255,0.45) 0 2px, transparent 2px 6px)", angle));
+                }
+
+                return vec![css];
+            }
+
             pub fn csss_from_colorcode(index: u32, colorcode: &str, whitetext: bool) -> Vec<CSS> {
                 let mut css = CSS::create(&format!("[event_index=\"{}\"]", index));
                 css.push_declaration("background-color", &format!("{} !important", colorcode));
@@ -255,9 +419,12 @@ pub mod web {
 
 pub mod input {
     use self::event::Event;
+    use crate::error::Error;
+    use crate::options::WeekStart;
+    use chrono::{NaiveDate, Weekday, Datelike, Duration};
 
     /// 入力ファイルをそのままstruct化したもの
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Input {
         // 該当年度
         pub year: i32,
@@ -265,23 +432,412 @@ pub mod input {
         pub title: String,
         // イベント一覧
         pub events: Vec<Event>,
+        // カレンダーに追加するcss
+        #[serde(default)]
+        pub css: Option<String>,
+        // 表示する最初の月(省略するとイベントのある最初の月)
+        #[serde(default)]
+        pub start: Option<MonthBound>,
+        // 表示する最後の月(省略するとイベントのある最後の月)
+        #[serde(default)]
+        pub end: Option<MonthBound>,
+        // 授業週を数えるための学期
+        #[serde(default)]
+        pub semester: Option<Semester>,
+        // イベントを選んで作る個別のカレンダー(全体・1年生向けなど)
+        #[serde(default)]
+        pub calendars: Vec<Calendar>,
+        // calendar()で選んだカレンダーに載せるイベントのindex(Noneならすべて)
+        // イベントのindex(色・アルファベット)は元のeventsのまま変えない
+        #[serde(skip)]
+        shown: Option<Vec<usize>>,
+    }
+
+    /// eventsの一部を載せたカレンダー
+    /// 同じイベントを複数のカレンダーに載せられる
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct Calendar {
+        // 出力ファイル名などに使う識別子 (例: "freshman")
+        pub id: String,
+        // カレンダーの名前 (例: "1年生向け")
+        pub name: String,
+        // タイトル(省略すると"{title} {name}")
+        #[serde(default)]
+        pub title: Option<String>,
+        // 載せるイベントの名前
+        pub events: Vec<String>,
+    }
+
+    /// 表示する範囲の端
+    /// 月(4)か、該当年度の日付("2019-04"や"2019-04-01")で指定する
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(untagged)]
+    pub enum MonthBound {
+        Month(u32),
+        Date(String),
+    }
+
+    impl MonthBound {
+        /// 範囲の始まりとしての日付
+        /// 月("2019-04"や4)で指定されていればその月の初日
+        pub fn first_day(&self, year: i32) -> Result<NaiveDate, Error> {
+            let (date, _) = self.parse(year)?;
+            return Ok(date);
+        }
+
+        /// 範囲の終わりとしての日付
+        /// 月("2019-04"や4)で指定されていればその月の末日
+        pub fn last_day(&self, year: i32) -> Result<NaiveDate, Error> {
+            let (date, has_day) = self.parse(year)?;
+            if has_day {
+                return Ok(date);
+            }
+            let next_month = if date.month() == 12 {
+                NaiveDate::from_ymd_opt(year + 1, 1, 1)
+            } else {
+                NaiveDate::from_ymd_opt(year, date.month() + 1, 1)
+            };
+            return next_month.map(|day| day - Duration::days(1)).ok_or_else(|| Error::InvalidBound(format!("{:?}", self)));
+        }
+
+        /// 指定された日付(月の指定なら初日)と、日まで指定されているか
+        fn parse(&self, year: i32) -> Result<(NaiveDate, bool), Error> {
+            match self {
+                MonthBound::Month(month) => match NaiveDate::from_ymd_opt(year, *month, 1) {
+                    Some(date) => Ok((date, false)),
+                    None => Err(Error::InvalidBound(month.to_string())),
+                },
+                MonthBound::Date(date) => {
+                    // 日を省略した"2019-04"も受け付ける
+                    let parsed = NaiveDate::parse_from_str(date, "%Y-%m-%d").map(|parsed| (parsed, true))
+                        .or_else(|_| NaiveDate::parse_from_str(&format!("{}-01", date), "%Y-%m-%d").map(|parsed| (parsed, false)));
+                    match parsed {
+                        Ok((parsed, has_day)) if parsed.year() == year => Ok((parsed, has_day)),
+                        _ => Err(Error::InvalidBound(date.clone())),
+                    }
+                }
+            }
+        }
+    }
+
+    /// 学期. 授業週("第3週")を数えるのに使う
+    /// 日付は"2019-04-08"のように指定する
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct Semester {
+        // 授業の始まる日(この日を含む週が第1週)
+        pub start: String,
+        // 授業の終わる日(省略するとカレンダーの最後まで数える)
+        #[serde(default)]
+        pub end: Option<String>,
+        // 休みの期間(授業週として数えない)
+        #[serde(default)]
+        pub breaks: Vec<Break>,
+    }
+
+    /// 休みの期間(startからendまで、両端を含む)
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct Break {
+        pub start: String,
+        pub end: String,
+    }
+
+    impl Semester {
+        /// first_dayから始まる週(カレンダーの1行)の授業週の番号
+        /// 学期の外や休みの週はNone
+        pub fn week_number(&self, first_day: NaiveDate, week_start: WeekStart) -> Result<Option<u32>, Error> {
+            let start = parse_semester_date(&self.start)?;
+            // 授業の始まる日を含む週の最初の日
+            let mut week = start - Duration::days(week_start.offset(start.weekday()) as i64);
+            if first_day < week || self.is_break(first_day)? {
+                return Ok(None);
+            }
+            if let Some(ref end) = self.end {
+                if first_day > parse_semester_date(end)? {
+                    return Ok(None);
+                }
+            }
+
+            // 休みの週を飛ばして数える
+            let mut number = 0;
+            while week <= first_day {
+                if !self.is_break(week)? {
+                    number += 1;
+                }
+                week += Duration::days(7);
+            }
+
+            return Ok(Some(number));
+        }
+
+        /// first_dayから始まる週が休みか
+        /// 週の平日(月曜から金曜)がすべて休みの期間に含まれていれば休み
+        fn is_break(&self, first_day: NaiveDate) -> Result<bool, Error> {
+            let mut breaks = Vec::new();
+            for range in &self.breaks {
+                breaks.push((parse_semester_date(&range.start)?, parse_semester_date(&range.end)?));
+            }
+
+            let weekdays = first_day.iter_days().take(7).filter(|day| day.weekday() != Weekday::Sat && day.weekday() != Weekday::Sun);
+            for day in weekdays {
+                if !breaks.iter().any(|(start, end)| *start <= day && day <= *end) {
+                    return Ok(false);
+                }
+            }
+            return Ok(true);
+        }
+    }
+
+    /// 学期の日付("2019-04-08")を読む
+    fn parse_semester_date(date: &str) -> Result<NaiveDate, Error> {
+        return NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| Error::InvalidSemester(date.to_string()));
+    }
+
+    impl Input {
+        /// json文字列からInputを作る
+        pub fn from_json(json: &str) -> Result<Input, Error> {
+            let input: Input = serde_json::from_str(json).map_err(Error::Parse)?;
+            input.check_calendars()?;
+            return Ok(input);
+        }
+
+        /// カレンダーのidは出力ファイルの名前に使うので、英数字・-・_だけで重複がないか確かめる
+        fn check_calendars(&self) -> Result<(), Error> {
+            for (i, calendar) in self.calendars.iter().enumerate() {
+                let id = &calendar.id;
+                if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                    return Err(Error::InvalidCalendarId(id.clone()));
+                }
+                if self.calendars[..i].iter().any(|other| &other.id == id) {
+                    return Err(Error::DuplicateCalendarId(id.clone()));
+                }
+            }
+            return Ok(());
+        }
+
+        /// jsonで書かれた入力ファイルを読み込む
+        pub fn from_file(path: &str) -> Result<Input, Error> {
+            let json = std::fs::read_to_string(path).map_err(|source| Error::Io { path: path.to_string(), source })?;
+            return Input::from_json(&json);
+        }
+
+        /// idのカレンダーに載せるイベントだけを表示するInputを作る
+        /// 載せないイベントもeventsには残すので、共有するイベントの色やアルファベットはどのカレンダーでも同じになる
+        pub fn calendar(&self, id: &str) -> Result<Input, Error> {
+            let calendar = self.calendars.iter().find(|calendar| calendar.id == id)
+                .ok_or_else(|| Error::UnknownCalendar(id.to_string()))?;
+            for name in &calendar.events {
+                if !self.events.iter().any(|event| &event.name == name) {
+                    return Err(Error::UnknownCalendarEvent { calendar: id.to_string(), event: name.clone() });
+                }
+            }
+
+            let shown = self.events.iter().enumerate()
+                .filter(|(i, event)| self.is_shown(*i) && calendar.events.contains(&event.name))
+                .map(|(i, _)| i)
+                .collect();
+            let title = match calendar.title {
+                Some(ref title) => title.clone(),
+                None => format!("{} {}", self.title, calendar.name),
+            };
+            return Ok(Input { title, calendars: Vec::new(), shown: Some(shown), ..self.clone() });
+        }
+
+        /// event_index番目のイベントを表示するか(calendar()で選んだカレンダーに載っているか)
+        pub fn is_shown(&self, event_index: usize) -> bool {
+            return match self.shown {
+                Some(ref shown) => shown.contains(&event_index),
+                None => event_index < self.events.len(),
+            };
+        }
+
+        /// 表示するイベントのindex一覧
+        pub fn shown_events(&self) -> Vec<usize> {
+            return (0..self.events.len()).filter(|i| self.is_shown(*i)).collect();
+        }
+
+        /// イベントのカテゴリの一覧(最初に現れた順)
+        pub fn categories(&self) -> Vec<&str> {
+            let mut categories: Vec<&str> = Vec::new();
+            for event in &self.events {
+                if let Some(ref category) = event.category {
+                    if !categories.contains(&category.as_str()) {
+                        categories.push(category);
+                    }
+                }
+            }
+            return categories;
+        }
+
+        /// 凡例に並べるカテゴリごとのイベントのindex一覧
+        /// カテゴリのないイベントは最後にNoneとしてまとめる
+        /// 表示しないイベントは含めず、表示するイベントのないカテゴリは除く
+        pub fn legend_groups(&self) -> Vec<(Option<&str>, Vec<usize>)> {
+            let mut groups: Vec<(Option<&str>, Vec<usize>)> = self.categories().into_iter().map(|category| (Some(category), Vec::new())).collect();
+            let mut uncategorized = Vec::new();
+            for (i, event) in self.events.iter().enumerate().filter(|(i, _)| self.is_shown(*i)) {
+                match groups.iter_mut().find(|(category, _)| event.category.is_some() && *category == event.category.as_deref()) {
+                    Some((_, events)) => events.push(i),
+                    None => uncategorized.push(i),
+                }
+            }
+            groups.retain(|(_, events)| events.len() > 0);
+            if uncategorized.len() > 0 {
+                groups.push((None, uncategorized));
+            }
+            return groups;
+        }
+
+        /// イベントの色の系統ごとのイベントのindex一覧
+        /// 同じカテゴリのイベントは1つの系統にまとめ、カテゴリのないイベントはそれぞれ1つの系統にする
+        pub fn color_families(&self) -> Vec<Vec<usize>> {
+            let mut families: Vec<(Option<&str>, Vec<usize>)> = Vec::new();
+            for (i, event) in self.events.iter().enumerate() {
+                let category = event.category.as_deref();
+                match families.iter_mut().find(|(family, _)| category.is_some() && *family == category) {
+                    Some((_, events)) => events.push(i),
+                    None => families.push((category, vec![i])),
+                }
+            }
+            return families.into_iter().map(|(_, events)| events).collect();
+        }
     }
 
     pub mod event {
         /// イベントの名前と日程
-        #[derive(Debug, Serialize, Deserialize)]
+        #[derive(Debug, Clone, Serialize, Deserialize)]
         pub struct Event {
             // イベント名
             pub name: String,
             // 日程
             pub dates: Vec<Date>,
+            // 開催時刻 (例: "18:30-20:00")
+            #[serde(default)]
+            pub time: Option<String>,
+            // 開催場所
+            #[serde(default)]
+            pub location: Option<String>,
+            // 詳細ページのURL
+            #[serde(default)]
+            pub url: Option<String>,
+            // カテゴリ (例: "新歓", "定例", "休み")
+            #[serde(default)]
+            pub category: Option<String>,
         }
 
         /// イベントの開催日程
-        #[derive(Debug, Serialize, Deserialize)]
+        #[derive(Debug, Clone, Serialize, Deserialize)]
         pub struct Date {
             pub month: u32,
             pub days: Vec<u32>,
+            // 開催日の状態(省略すると確定)
+            #[serde(default)]
+            pub status: Status,
+        }
+
+        /// 開催日の状態
+        #[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+        #[serde(rename_all = "lowercase")]
+        pub enum Status {
+            /// 確定
+            #[default]
+            Confirmed,
+            /// 未定
+            Tentative,
+            /// 中止
+            Cancelled,
+        }
+
+        impl Status {
+            /// htmlのclassに使う名前
+            pub fn name(&self) -> &'static str {
+                match self {
+                    Status::Confirmed => "confirmed",
+                    Status::Tentative => "tentative",
+                    Status::Cancelled => "cancelled",
+                }
+            }
+
+            /// 日付やイベント名に添える説明. 確定ならNone
+            pub fn label(&self) -> Option<&'static str> {
+                match self {
+                    Status::Confirmed => None,
+                    Status::Tentative => Some("未定"),
+                    Status::Cancelled => Some("中止"),
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn date(month: u32, day: u32) -> NaiveDate {
+            return NaiveDate::from_ymd_opt(2019, month, day).unwrap();
+        }
+
+        /// 2019-04-10(水)に始まり2019-07-26(金)に終わる学期
+        /// 4/27から5/6のゴールデンウィークと、6/5(水)から6/11(火)の週をまたぐ休みがある
+        fn semester() -> Semester {
+            return Semester {
+                start: "2019-04-10".to_string(),
+                end: Some("2019-07-26".to_string()),
+                breaks: vec![
+                    Break { start: "2019-04-27".to_string(), end: "2019-05-06".to_string() },
+                    Break { start: "2019-06-05".to_string(), end: "2019-06-11".to_string() },
+                ],
+            };
+        }
+
+        #[test]
+        fn semester_starting_mid_week_counts_its_week() {
+            let semester = semester();
+            assert_eq!(semester.week_number(date(3, 31), WeekStart::Sunday).unwrap(), None);
+            assert_eq!(semester.week_number(date(4, 7), WeekStart::Sunday).unwrap(), Some(1));
+            assert_eq!(semester.week_number(date(4, 1), WeekStart::Monday).unwrap(), None);
+            assert_eq!(semester.week_number(date(4, 8), WeekStart::Monday).unwrap(), Some(1));
+        }
+
+        #[test]
+        fn break_weeks_are_skipped() {
+            let semester = semester();
+            assert_eq!(semester.week_number(date(4, 21), WeekStart::Sunday).unwrap(), Some(3));
+            assert_eq!(semester.week_number(date(4, 28), WeekStart::Sunday).unwrap(), None);
+            assert_eq!(semester.week_number(date(5, 5), WeekStart::Sunday).unwrap(), Some(4));
+            assert_eq!(semester.week_number(date(4, 22), WeekStart::Monday).unwrap(), Some(3));
+            assert_eq!(semester.week_number(date(4, 29), WeekStart::Monday).unwrap(), None);
+            assert_eq!(semester.week_number(date(5, 6), WeekStart::Monday).unwrap(), Some(4));
+        }
+
+        #[test]
+        fn break_across_weeks_is_not_a_break_week() {
+            let semester = semester();
+            assert!(!semester.is_break(date(6, 2)).unwrap());
+            assert!(!semester.is_break(date(6, 9)).unwrap());
+            assert!(!semester.is_break(date(6, 3)).unwrap());
+            assert!(!semester.is_break(date(6, 10)).unwrap());
+            assert!(semester.is_break(date(4, 28)).unwrap());
+            assert!(semester.is_break(date(4, 29)).unwrap());
+            assert_eq!(semester.week_number(date(6, 2), WeekStart::Sunday).unwrap(), Some(8));
+            assert_eq!(semester.week_number(date(6, 9), WeekStart::Sunday).unwrap(), Some(9));
+            assert_eq!(semester.week_number(date(6, 3), WeekStart::Monday).unwrap(), Some(8));
+            assert_eq!(semester.week_number(date(6, 10), WeekStart::Monday).unwrap(), Some(9));
+        }
+
+        #[test]
+        fn weeks_after_end_have_no_number() {
+            let semester = semester();
+            assert_eq!(semester.week_number(date(7, 21), WeekStart::Sunday).unwrap(), Some(15));
+            assert_eq!(semester.week_number(date(7, 28), WeekStart::Sunday).unwrap(), None);
+            assert_eq!(semester.week_number(date(7, 22), WeekStart::Monday).unwrap(), Some(15));
+            assert_eq!(semester.week_number(date(7, 29), WeekStart::Monday).unwrap(), None);
+        }
+
+        #[test]
+        fn without_end_counts_to_the_end_of_the_calendar() {
+            let semester = Semester { end: None, ..semester() };
+            assert_eq!(semester.week_number(date(7, 28), WeekStart::Sunday).unwrap(), Some(16));
+            assert_eq!(semester.week_number(date(7, 29), WeekStart::Monday).unwrap(), Some(16));
         }
     }
 }
@@ -304,48 +860,23 @@ pub mod date {
     }
 
     impl MonthNames {
-        pub fn from_u32(i: u32) -> MonthNames {
+        /// 0始まりの月(chrono::Datelike::month0)からMonthNamesを得る
+        pub fn from_u32(i: u32) -> Option<MonthNames> {
             match i {
-                0 => {
-                    MonthNames::January
-                }
-                1 => {
-                    MonthNames::February
-                }
-                2 => {
-                    MonthNames::March
-                }
-                3 => {
-                    MonthNames::April
-                }
-                4 => {
-                    MonthNames::May
-                }
-                5 => {
-                    MonthNames::June
-                }
-                6 => {
-                    MonthNames::July
-                }
-                7 => {
-                    MonthNames::August
-                }
-                8 => {
-                    MonthNames::September
-                }
-                9 => {
-                    MonthNames::October
-                }
-                10 => {
-                    MonthNames::November
-                }
-                11 => {
-                    MonthNames::December
-                }
-                _ => {
-                    panic!(format!("There is no {}th month.", i))
-                }
+                0 => Some(MonthNames::January),
+                1 => Some(MonthNames::February),
+                2 => Some(MonthNames::March),
+                3 => Some(MonthNames::April),
+                4 => Some(MonthNames::May),
+                5 => Some(MonthNames::June),
+                6 => Some(MonthNames::July),
+                7 => Some(MonthNames::August),
+                8 => Some(MonthNames::September),
+                9 => Some(MonthNames::October),
+                10 => Some(MonthNames::November),
+                11 => Some(MonthNames::December),
+                _ => None,
             }
         }
     }
-}
\ No newline at end of file
+}
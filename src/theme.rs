@@ -0,0 +1,125 @@
+/// カレンダーの配色とフォント
+#[derive(Debug, PartialEq)]
+pub struct Theme {
+    /// テーマの名前(--themeで指定する)
+    pub name: &'static str,
+    /// ページの背景色
+    pub background: &'static str,
+    /// 文字色
+    pub text: &'static str,
+    /// navの背景色
+    pub nav_background: &'static str,
+    /// navの文字色
+    pub nav_text: &'static str,
+    /// navのフォント
+    pub nav_font: &'static str,
+    /// 強調色(凡例の左の線)
+    pub accent: &'static str,
+    /// 凡例の背景色
+    pub legend_background: &'static str,
+    /// 月の見出しの文字色
+    pub title: &'static str,
+    /// ナビゲーション用のアイコンの色
+    pub icon: &'static str,
+    /// 表の罫線の色
+    pub border: &'static str,
+    /// 日曜日の文字色
+    pub sunday: &'static str,
+    /// 土曜日の文字色
+    pub saturday: &'static str,
+    /// イベントのマーカーの色. 空ならMakerCSSsの色を使う
+    pub palette: &'static [&'static str],
+    /// 端末がダークモードのときに代わりに使うテーマ
+    pub dark: Option<&'static Theme>,
+}
+
+/// 従来の配色
+pub const LIGHT: Theme = Theme {
+    name: "light",
+    background: "#ffffff",
+    text: "rgba(0,0,0,0.87)",
+    nav_background: "#ee6e73",
+    nav_text: "#ffffff",
+    nav_font: "Menlo",
+    accent: "#ee6e73",
+    legend_background: "rgb(244,245,246)",
+    title: "#757575",
+    icon: "#e0e0e0",
+    border: "rgba(0,0,0,0.12)",
+    sunday: "#F44336",
+    saturday: "#2196F3",
+    palette: &[],
+    dark: Some(&DARK),
+};
+
+/// 暗い背景の配色
+pub const DARK: Theme = Theme {
+    name: "dark",
+    background: "#121212",
+    text: "rgba(255,255,255,0.87)",
+    nav_background: "#1f1f1f",
+    nav_text: "#f5f5f5",
+    nav_font: "Menlo",
+    accent: "#f48fb1",
+    legend_background: "#1e1e1e",
+    title: "#bdbdbd",
+    icon: "#424242",
+    border: "rgba(255,255,255,0.12)",
+    sunday: "#ef9a9a",
+    saturday: "#90caf9",
+    palette: &["#1e88e5", "#e53935", "#00897b", "#7e57c2", "#ef6c00"],
+    dark: None,
+};
+
+/// 白黒を基調にしたコントラストの高い配色
+pub const HIGH_CONTRAST: Theme = Theme {
+    name: "high-contrast",
+    background: "#ffffff",
+    text: "#000000",
+    nav_background: "#000000",
+    nav_text: "#ffffff",
+    nav_font: "Menlo",
+    accent: "#000000",
+    legend_background: "#ffffff",
+    title: "#000000",
+    icon: "#000000",
+    border: "#000000",
+    sunday: "#b71c1c",
+    saturday: "#0d47a1",
+    palette: &["#0d47a1", "#b71c1c", "#004d40", "#311b92", "#bf360c"],
+    dark: None,
+};
+
+/// サークルのロゴに合わせた配色
+pub const CLUB_BRAND: Theme = Theme {
+    name: "club-brand",
+    background: "#ffffff",
+    text: "rgba(0,0,0,0.87)",
+    nav_background: "#3949ab",
+    nav_text: "#ffffff",
+    nav_font: "Menlo",
+    accent: "#ffb300",
+    legend_background: "#e8eaf6",
+    title: "#3949ab",
+    icon: "#c5cae9",
+    border: "rgba(0,0,0,0.12)",
+    sunday: "#F44336",
+    saturday: "#2196F3",
+    palette: &[],
+    dark: Some(&DARK),
+};
+
+/// 組み込みのテーマ一覧
+pub const THEMES: [&Theme; 4] = [&LIGHT, &DARK, &HIGH_CONTRAST, &CLUB_BRAND];
+
+impl Theme {
+    /// 名前から組み込みのテーマを得る
+    pub fn from_name(name: &str) -> Option<&'static Theme> {
+        return THEMES.iter().find(|theme| theme.name == name).copied();
+    }
+
+    /// 組み込みのテーマの名前をカンマ区切りで並べる
+    pub fn names() -> String {
+        return THEMES.iter().map(|theme| theme.name).collect::<Vec<&str>>().join(", ");
+    }
+}
@@ -0,0 +1,42 @@
+extern crate acscg;
+
+mod common;
+
+use acscg::RenderOptions;
+
+const INPUT: &str = r#"{
+  "year": 2019,
+  "title": "Amusement Creators",
+  "events": [
+    { "name": "合同新歓", "dates": [{ "month": 4, "days": [8] }] },
+    { "name": "ミーティング", "dates": [{ "month": 4, "days": [10] }] }
+  ]
+}"#;
+
+#[test]
+fn html_has_lang() {
+    let html = common::render(INPUT, &RenderOptions::default());
+    assert!(html.contains(r#"<html lang="ja">"#));
+}
+
+#[test]
+fn calendar_table_has_caption_and_header_scope() {
+    let html = common::render(INPUT, &RenderOptions::default());
+    assert!(html.contains("<caption class=\"visually-hidden\">2019年4月のカレンダー</caption>"));
+    assert_eq!(html.matches("<th ").count(), html.matches(r#"scope="col""#).count());
+}
+
+#[test]
+fn event_days_have_aria_label_and_letter() {
+    let html = common::render(INPUT, &RenderOptions::default());
+    assert!(html.contains(r#"aria-label="4月10日 水曜日: ミーティング""#));
+    assert!(html.contains(r#"aria-label="4月11日 木曜日""#));
+    assert!(html.contains(r#"<span class="marker-letter" aria-hidden="true">B</span>"#));
+}
+
+#[test]
+fn legend_is_labeled() {
+    let html = common::render(INPUT, &RenderOptions::default());
+    assert!(html.contains(r#"<ul class="collection" aria-label="凡例">"#));
+    assert!(html.contains(r#"<span class="circled" event_index="0" aria-hidden="true">10</span>"#));
+}
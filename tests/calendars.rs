@@ -0,0 +1,48 @@
+extern crate acscg;
+
+mod common;
+
+use acscg::{Input, RenderOptions};
+
+const INPUT: &str = r#"{
+  "year": 2019,
+  "title": "Amusement Creators",
+  "events": [
+    { "name": "合同新歓", "dates": [{ "month": 4, "days": [8] }] },
+    { "name": "幹部会", "dates": [{ "month": 4, "days": [10] }] }
+  ],
+  "calendars": [
+    { "id": "all", "name": "全体", "events": ["合同新歓", "幹部会"] },
+    { "id": "exec", "name": "幹部", "events": ["幹部会"] }
+  ]
+}"#;
+
+#[test]
+fn shared_event_keeps_index() {
+    let input = common::input(INPUT);
+    let all = input.calendar("all").unwrap();
+    let exec = input.calendar("exec").unwrap();
+    assert_eq!(exec.shown_events(), vec![1]);
+
+    let options = RenderOptions::default();
+    assert_eq!(options.event_color(&all, 1).unwrap(), options.event_color(&exec, 1).unwrap());
+
+    let html = acscg::render(&exec, &options).unwrap();
+    assert!(html.contains(r#"<span class="marker-letter" aria-hidden="true">B</span>"#));
+    assert!(html.contains(r#"<span class="circled" event_index="1" aria-hidden="true">10</span>"#));
+    assert!(!html.contains("合同新歓"));
+}
+
+#[test]
+fn unknown_calendar_is_an_error() {
+    let input = common::input(INPUT);
+    assert!(matches!(input.calendar("freshman"), Err(acscg::Error::UnknownCalendar(_))));
+}
+
+#[test]
+fn calendar_ids_are_checked() {
+    let path = INPUT.replace(r#""id": "exec""#, r#""id": "../exec""#);
+    assert!(matches!(Input::from_json(&path), Err(acscg::Error::InvalidCalendarId(_))));
+    let duplicate = INPUT.replace(r#""id": "exec""#, r#""id": "all""#);
+    assert!(matches!(Input::from_json(&duplicate), Err(acscg::Error::DuplicateCalendarId(_))));
+}
@@ -0,0 +1,39 @@
+#![allow(clippy::needless_return)]
+
+extern crate acscg;
+
+mod common;
+
+use acscg::{Error, Input, RenderOptions, MAX_EVENTS_PER_CATEGORY};
+
+/// 1つのカテゴリにcount個のイベントを持つ入力
+fn input_with_category(count: usize) -> Input {
+    let events: Vec<String> = (0..count)
+        .map(|i| format!(r#"{{ "name": "e{}", "category": "定例", "dates": [{{ "month": 4, "days": [{}] }}] }}"#, i, i % 28 + 1))
+        .collect();
+    let json = format!(r#"{{ "year": 2019, "title": "t", "events": [{}] }}"#, events.join(","));
+    return common::input(&json);
+}
+
+#[test]
+fn category_colors_are_distinct() {
+    let input = input_with_category(MAX_EVENTS_PER_CATEGORY);
+    let options = RenderOptions::default();
+    let mut colors: Vec<String> = (0..MAX_EVENTS_PER_CATEGORY).map(|i| options.event_color(&input, i as i32).unwrap()).collect();
+    colors.sort();
+    colors.dedup();
+    assert_eq!(colors.len(), MAX_EVENTS_PER_CATEGORY);
+    assert!(acscg::render(&input, &options).is_ok());
+}
+
+#[test]
+fn too_many_events_in_category() {
+    let input = input_with_category(200);
+    match acscg::render(&input, &RenderOptions::default()) {
+        Err(Error::TooManyEvents { limit, count }) => {
+            assert_eq!(limit, MAX_EVENTS_PER_CATEGORY);
+            assert_eq!(count, 200);
+        }
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
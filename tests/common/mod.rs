@@ -0,0 +1,15 @@
+#![allow(clippy::needless_return, dead_code)]
+
+//! 結合テストで共通に使う入力の読み込みとhtmlの生成
+
+use acscg::{Input, RenderOptions};
+
+/// json文字列の入力を読み込む
+pub fn input(json: &str) -> Input {
+    return Input::from_json(json).unwrap();
+}
+
+/// json文字列の入力からhtmlを生成する
+pub fn render(json: &str, options: &RenderOptions) -> String {
+    return acscg::render(&input(json), options).unwrap();
+}
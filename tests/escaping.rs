@@ -0,0 +1,35 @@
+extern crate acscg;
+
+mod common;
+
+use acscg::{RenderOptions, CellMode};
+
+const INPUT: &str = r#"{
+  "year": 2019,
+  "title": "Q&A <Night>",
+  "events": [
+    {
+      "name": "A \"quoted\" <b>x</b>",
+      "category": "C&\"D\"",
+      "url": "https://example.com/?a=1&b=2",
+      "dates": [{ "month": 4, "days": [10] }]
+    }
+  ]
+}"#;
+
+#[test]
+fn attributes_are_escaped() {
+    let html = common::render(INPUT, &RenderOptions::builder().cells(CellMode::Detailed).build());
+    assert!(html.contains(r#"aria-label="4月10日 水曜日: A &quot;quoted&quot; &lt;b&gt;x&lt;/b&gt;""#));
+    assert!(html.contains(r#"data-category="C&amp;&quot;D&quot;""#));
+    assert!(html.contains(r#"href="https://example.com/?a=1&amp;b=2""#));
+    assert!(html.contains(r#"title="A &quot;quoted&quot; &lt;b&gt;x&lt;/b&gt;""#));
+    assert!(!html.contains("<b>x</b>"));
+}
+
+#[test]
+fn text_is_escaped() {
+    let html = common::render(INPUT, &RenderOptions::default());
+    assert!(html.contains("Q&amp;A &lt;Night&gt;"));
+    assert!(html.contains("A &quot;quoted&quot; &lt;b&gt;x&lt;/b&gt;"));
+}
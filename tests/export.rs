@@ -0,0 +1,31 @@
+extern crate acscg;
+
+mod common;
+
+use acscg::{RenderOptions, create_svg, create_png};
+
+const INPUT: &str = r#"{
+  "year": 2019,
+  "title": "Q&A <Night>",
+  "events": [
+    { "name": "A & B \"talk\"", "dates": [{ "month": 4, "days": [8] }] },
+    { "name": "<b>x</b>", "dates": [{ "month": 4, "days": [10] }] }
+  ]
+}"#;
+
+#[test]
+fn svg_escapes_names() {
+    let input = common::input(INPUT);
+    let svg = create_svg::create(&input, create_svg::Size::default(), &RenderOptions::default()).unwrap();
+    assert!(svg.contains("Q&amp;A &lt;Night&gt;"));
+    assert!(svg.contains("A &amp; B &quot;talk&quot;"));
+    assert!(!svg.contains("<b>"));
+}
+
+#[test]
+fn svg_with_escaped_names_rasterizes() {
+    let input = common::input(INPUT);
+    let svg = create_svg::create(&input, create_svg::Size::default(), &RenderOptions::default()).unwrap();
+    let png = create_png::create(&svg, None).unwrap();
+    assert!(png.starts_with(b"\x89PNG"));
+}